@@ -0,0 +1,142 @@
+//! Map storage benchmarks: lookups and random ticks on big maps.
+//!
+//! `vec_scan` is the old storage (list of blocks searched by position) for comparison.
+//!
+//!     $ cargo bench -p ppl-game --bench map
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ppl_game::{
+    assets::blocks::{generic::Generic, wheat::Wheat, Block, BlockBehavior},
+    game::{Game, GameHandle},
+    map::{grid::BlockGrid, MapData},
+    player::PlayerInventory,
+    things::{BlockData, BlockUpdateContext, CollisionTy},
+    ui::{BlockTy, Point},
+    ui_impls::headless,
+};
+use rand::Rng;
+
+const SIZES: [u16; 3] = [50, 200, 500];
+
+/// Square map of wheat fields with walls on every 7th block
+fn blocks(side: u16) -> impl Iterator<Item = BlockData> {
+    (0..side).flat_map(move |y| {
+        (0..side).map(move |x| {
+            let (collision, ty, block): (_, _, Block) = if (x + y) % 7 == 0 {
+                (CollisionTy::Collision, BlockTy::Wall, Generic.into())
+            } else {
+                (CollisionTy::CanUse, BlockTy::Wheat, Wheat::new().into())
+            };
+            BlockData::new(Point(x, y), collision, ty, block)
+        })
+    })
+}
+
+fn grid(side: u16) -> BlockGrid {
+    let mut grid = BlockGrid::with_size(Point(side, side));
+    blocks(side).for_each(|b| _ = grid.insert(b));
+    grid
+}
+
+/// Positions spread over the map
+fn probes(side: u16) -> Vec<Point> {
+    (0..1000u32)
+        .map(|i| {
+            let i = i * 7919;
+            Point((i % side as u32) as u16, (i / 13 % side as u32) as u16)
+        })
+        .collect()
+}
+
+fn lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lookup_1000");
+    for side in SIZES {
+        let probes = probes(side);
+        let grid = grid(side);
+        group.bench_with_input(BenchmarkId::new("grid", side), &probes, |b, probes| {
+            b.iter(|| {
+                probes
+                    .iter()
+                    .filter(|&&p| grid.get(black_box(p)).is_some())
+                    .count()
+            })
+        });
+        let list: Vec<BlockData> = blocks(side).collect();
+        group.bench_with_input(BenchmarkId::new("vec_scan", side), &probes, |b, probes| {
+            b.iter(|| {
+                probes
+                    .iter()
+                    .filter(|&&p| list.iter().any(|b| b.state.pos() == black_box(p)))
+                    .count()
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Random tick of the old storage: blocks and their neighbours are searched in the list
+fn vec_random_tick(
+    list: &mut [BlockData],
+    handle: &mut GameHandle<headless::Context>,
+    inventory: &mut PlayerInventory,
+) {
+    let mut other_updates = Vec::new();
+    for i in 0..list.len() {
+        if handle.rng.gen_range(0..100) >= 15 {
+            continue;
+        }
+        let pos = list[i].state.pos();
+        let neighbours = pos
+            .neighbours()
+            .filter_map(|p| list.iter().find(|b| b.state.pos() == p))
+            .map(|b| b.state.clone())
+            .collect();
+        let BlockData { state, block } = &mut list[i];
+        let update = BlockUpdateContext {
+            game_handle: handle,
+            this: state,
+            player_inventory: inventory,
+            neighbours,
+        };
+        other_updates.push(block.update(update).unwrap().other);
+    }
+    for (p, s) in other_updates.into_iter().flatten() {
+        if let Some(data) = list.iter_mut().find(|b| b.state.pos() == p) {
+            data.state.merge_with(s);
+        }
+    }
+}
+
+fn random_tick(c: &mut Criterion) {
+    let mut group = c.benchmark_group("random_tick");
+    group.sample_size(20);
+    for side in SIZES {
+        let mut game = Game::new_with_seed(headless::Context::new(), 42);
+        game.maps.farm = MapData {
+            size: Point(side, side),
+            blocks: grid(side),
+            exits: Vec::new(),
+        };
+        group.bench_function(BenchmarkId::new("grid", side), |b| {
+            b.iter(|| {
+                game.maps
+                    .do_random_tick(&mut game.handle, &mut game.player_inventory)
+                    .unwrap()
+            })
+        });
+        // scan of the biggest map takes over ten seconds per tick
+        if side > 200 {
+            continue;
+        }
+        let mut list: Vec<BlockData> = blocks(side).collect();
+        group.bench_function(BenchmarkId::new("vec_scan", side), |b| {
+            b.iter(|| vec_random_tick(&mut list, &mut game.handle, &mut game.player_inventory))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, lookup, random_tick);
+criterion_main!(benches);
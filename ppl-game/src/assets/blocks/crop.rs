@@ -0,0 +1,93 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::{
+        blocks::{BlockBehavior, BlockUpdates},
+        registry::{BehaviorDef, BlockDef, Registry, ThingName},
+    },
+    things::{BlockUpdateContext, CollisionTy},
+    ui::{self, BlockTy},
+};
+
+/// Crop declared in [registry](crate::assets::registry) with [`BehaviorDef::Crop`]. Ripe
+/// crop is its own block, growing one is shown as `growing` block and has no collision.
+#[derive(Serialize, Deserialize)]
+pub struct Crop {
+    /// Name of ripe crop block
+    block: ThingName,
+    /// Ticks left until crop is ripe
+    tick: u8,
+}
+
+impl Crop {
+    /// Creates ripe crop
+    pub fn new(block: ThingName) -> Crop {
+        Crop { block, tick: 0 }
+    }
+
+    fn def(&self) -> Option<&'static BlockDef> {
+        Registry::get().block(self.block)
+    }
+}
+
+impl BlockBehavior for Crop {
+    fn update<UI: ui::Context>(
+        &mut self,
+        ctx: BlockUpdateContext<'_, UI>,
+    ) -> Result<BlockUpdates, UI::Error> {
+        let Some(def) = self.def() else {
+            return BlockUpdates::new().ok();
+        };
+        if self.tick != 0 {
+            self.tick -= 1;
+            if self.tick == 0 {
+                ctx.this.ty = BlockTy::Custom(self.block);
+                ctx.this.collision = def.collision;
+            }
+        }
+        BlockUpdates::new().ok()
+    }
+
+    fn interact<UI: ui::Context>(
+        &mut self,
+        mut ctx: BlockUpdateContext<'_, UI>,
+    ) -> Result<BlockUpdates, UI::Error> {
+        let Some(BlockDef {
+            behavior:
+                BehaviorDef::Crop {
+                    grow_ticks,
+                    growing,
+                    drops,
+                    effects,
+                },
+            ..
+        }) = self.def()
+        else {
+            return BlockUpdates::new().ok();
+        };
+        if self.tick != 0 {
+            return BlockUpdates::new().ok();
+        }
+
+        for drop in drops {
+            if ctx.rng().gen_range(0..100) < drop.chance {
+                let item = drop.item.create(ctx.rng());
+                ctx.player_inventory.items.push(item);
+            }
+        }
+        let handle = &mut *ctx.game_handle;
+        for effect in effects {
+            if let Some(item) = effect.apply(&mut handle.player, &mut handle.rng) {
+                ctx.player_inventory.items.push(item);
+            }
+        }
+        let limits = ctx.player_limits();
+        ctx.game_handle.player.clamp_to(&limits);
+
+        self.tick = (*grow_ticks).max(1);
+        ctx.this.ty = *growing;
+        ctx.this.collision = CollisionTy::NoCollision;
+        BlockUpdates::new().ok()
+    }
+}
@@ -1,4 +1,7 @@
+use serde::{Deserialize, Serialize};
+
 use crate::assets::blocks::BlockBehavior;
 
+#[derive(Serialize, Deserialize)]
 pub struct Generic;
 impl BlockBehavior for Generic {}
@@ -1,11 +1,19 @@
 use enum_dispatch::enum_dispatch;
+use serde::{Deserialize, Serialize};
 
 pub mod wheat;
 use wheat::Wheat;
 pub mod generic;
 use generic::Generic;
+pub mod npc;
+use npc::Npc;
+pub mod water;
+use water::Water;
+pub mod crop;
+use crop::Crop;
 
 use crate::{
+    player::Player,
     things::{BlockUpdateContext, PartialBlockState},
     ui::{self, Point},
 };
@@ -14,14 +22,14 @@ use crate::{
 ///
 /// # Examples
 /// ```rust
-/// use ppl_game::{things::PartialBlockState, ui::Point, assets::blocks::BlockUpdates};
+/// use ppl_game::{things::PartialBlockState, ui::{BlockTy, Point}, assets::blocks::BlockUpdates};
 ///
 /// /// Some update function...
 /// fn update() -> Result<BlockUpdates, ()> {
 ///     BlockUpdates::new() // creates new empty update
 ///         .other(Point(1, 1), PartialBlockState {
-///             pos: Some(Point(11, 10)), // move block at (1, 1) to
-///             ..Default::default()      // (11, 10)
+///             ty: Some(BlockTy::Wheat), // turn block at (1, 1)
+///             ..Default::default()      // into wheat
 ///         })
 ///         .ok() // wrap into Result::<BlockUpdates, _>::Ok(...)
 /// }
@@ -81,10 +89,20 @@ pub trait BlockBehavior {
     ) -> Result<BlockUpdates, UI::Error> {
         BlockUpdates::new().ok()
     }
+    /// `player` standing on block interacts with it even if block has no
+    /// [`CanUse`](crate::things::CollisionTy::CanUse) collision. Should be `true` only if
+    /// interaction would do something, otherwise player uses block nearby.
+    fn interacts_from_inside(&self, player: &Player) -> bool {
+        false
+    }
 }
 
 #[enum_dispatch(BlockBehavior)]
+#[derive(Serialize, Deserialize)]
 pub enum Block {
     Generic,
     Wheat,
+    Water,
+    Npc,
+    Crop,
 }
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::{
+        blocks::{BlockBehavior, BlockUpdates},
+        dialogues::Dialogue,
+    },
+    things::BlockUpdateContext,
+    ui,
+};
+
+/// NPC that talks with player. See [`crate::assets::dialogues`]
+#[derive(Serialize, Deserialize)]
+pub struct Npc {
+    /// Name of embedded dialogue
+    dialogue: String,
+}
+
+impl Npc {
+    /// Creates NPC with dialogue. Returns `None` if there is no such dialogue.
+    pub fn new(dialogue: &str) -> Option<Npc> {
+        Dialogue::source(dialogue)?;
+        Some(Npc {
+            dialogue: dialogue.to_owned(),
+        })
+    }
+}
+
+impl BlockBehavior for Npc {
+    fn interact<UI: ui::Context>(
+        &mut self,
+        ctx: BlockUpdateContext<'_, UI>,
+    ) -> Result<BlockUpdates, UI::Error> {
+        if let Some(dialogue) = Dialogue::load(&self.dialogue) {
+            ctx.game_handle.start_dialogue(dialogue);
+        }
+        BlockUpdates::new().ok()
+    }
+}
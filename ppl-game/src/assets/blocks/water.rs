@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::blocks::{BlockBehavior, BlockUpdates},
+    things::BlockUpdateContext,
+    ui,
+};
+
+/// Water source. Player fills one bucket per interaction.
+#[derive(Serialize, Deserialize)]
+pub struct Water;
+
+impl BlockBehavior for Water {
+    fn interact<UI: ui::Context>(
+        &mut self,
+        ctx: BlockUpdateContext<'_, UI>,
+    ) -> Result<BlockUpdates, UI::Error> {
+        let limits = ctx.player_limits();
+        let water = &mut ctx.game_handle.player.water;
+        if *water < limits.water {
+            *water += 1;
+        }
+        BlockUpdates::new().ok()
+    }
+}
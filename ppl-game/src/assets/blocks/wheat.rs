@@ -1,43 +1,97 @@
-use rand::{thread_rng, Rng};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     assets::{
         blocks::{BlockBehavior, BlockUpdates},
         items::bread::Bread,
     },
+    player::Player,
     things::{BlockUpdateContext, CollisionTy},
     ui::{self, BlockTy},
 };
 
+/// Ticks wheat grows after harvest
+const GROW_TICKS: u8 = 7;
+/// Ticks skipped when growing wheat is watered
+const WATER_TICKS: u8 = 3;
+/// Moisture of wheat next to water or just watered. Lost by one every update.
+const MAX_MOISTURE: u8 = 6;
+/// Dry growing wheat grows with chance 1 of `DRY_GROW_CHANCE`
+const DRY_GROW_CHANCE: u32 = 3;
+
+/// Wheat. Grows faster next to water, slower when dry. Ripe wheat withers without water.
+#[derive(Serialize, Deserialize)]
 pub struct Wheat {
     tick: u8,
+    moisture: u8,
 }
 
 impl Wheat {
     pub fn new() -> Wheat {
-        Wheat { tick: 0 }
+        Wheat {
+            tick: 0,
+            moisture: MAX_MOISTURE,
+        }
+    }
+
+    /// Block type of growing wheat with current moisture
+    fn growing_ty(&self) -> BlockTy {
+        if self.moisture == 0 {
+            BlockTy::DryWheat
+        } else {
+            BlockTy::GrowingWheat
+        }
+    }
+
+    /// Reduces ticks left and makes wheat ripe when they end
+    fn grow<UI: ui::Context>(&mut self, ctx: &mut BlockUpdateContext<'_, UI>, ticks: u8) {
+        self.tick = self.tick.saturating_sub(ticks);
+        if self.tick == 0 {
+            ctx.this.ty = BlockTy::Wheat;
+            ctx.this.collision = CollisionTy::CanUse;
+        } else {
+            ctx.this.ty = self.growing_ty();
+        }
+    }
+
+    /// Turns wheat into growing one
+    fn reset<UI: ui::Context>(&mut self, ctx: &mut BlockUpdateContext<'_, UI>) {
+        self.tick = GROW_TICKS;
+        ctx.this.ty = self.growing_ty();
+        ctx.this.collision = CollisionTy::NoCollision;
     }
 }
 
 impl BlockBehavior for Wheat {
     fn update<UI: ui::Context>(
         &mut self,
-        ctx: BlockUpdateContext<'_, UI>,
+        mut ctx: BlockUpdateContext<'_, UI>,
     ) -> Result<BlockUpdates, UI::Error> {
-        if self.tick != 0 {
-            self.tick -= 1;
+        let near_water = ctx.has_neighbour(BlockTy::Water);
+        if near_water {
+            self.moisture = MAX_MOISTURE;
+        } else {
+            self.moisture = self.moisture.saturating_sub(1);
+        }
 
-            if self.tick == 0 {
-                ctx.this.ty = BlockTy::Wheat;
-                ctx.this.collision = CollisionTy::CanUse;
+        if self.tick != 0 {
+            if near_water {
+                self.grow(&mut ctx, 2);
+            } else if self.moisture != 0 || ctx.rng().gen_ratio(1, DRY_GROW_CHANCE) {
+                self.grow(&mut ctx, 1);
+            } else {
+                ctx.this.ty = self.growing_ty();
             }
+        } else if self.moisture == 0 {
+            self.reset(&mut ctx);
         }
         BlockUpdates::new().ok()
     }
 
     fn interact<UI: ui::Context>(
         &mut self,
-        ctx: BlockUpdateContext<'_, UI>,
+        mut ctx: BlockUpdateContext<'_, UI>,
     ) -> Result<BlockUpdates, UI::Error> {
         if self.tick == 0 {
             let limits = ctx.player_limits();
@@ -46,18 +100,24 @@ impl BlockBehavior for Wheat {
                 return BlockUpdates::new().ok();
             }
 
-            ctx.game_handle.player.wheat += thread_rng().gen_range(1..=3);
+            ctx.game_handle.player.wheat += ctx.rng().gen_range(1..=3);
             if ctx.game_handle.player.wheat > limits.wheat {
                 ctx.game_handle.player.wheat = limits.wheat;
             }
-            self.tick = 7;
-
-            ctx.this.ty = BlockTy::GrowingWheat;
-            ctx.this.collision = CollisionTy::NoCollision;
+            self.reset(&mut ctx);
 
             ctx.player_inventory.items.push(Bread::init());
+        } else if ctx.game_handle.player.water > 0 {
+            ctx.game_handle.player.water -= 1;
+            self.moisture = MAX_MOISTURE;
+            self.grow(&mut ctx, WATER_TICKS);
         }
 
         BlockUpdates::new().ok()
     }
+
+    /// Growing wheat is watered by player standing on it, if player has water
+    fn interacts_from_inside(&self, player: &Player) -> bool {
+        self.tick == 0 || player.water > 0
+    }
 }
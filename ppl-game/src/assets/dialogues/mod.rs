@@ -0,0 +1,243 @@
+//! # NPC dialogues
+//!
+//! Dialogues are trees of [`Node`]s defined as [RON](https://docs.rs/ron) files in this
+//! directory. Each node has lines said by NPC and numbered choices of the player. Choice may
+//! have [`Condition`]s on [`Player`] fields and [`Effect`]s that are applied when it's chosen.
+//!
+//! # Example
+//! ```
+//! use ppl_game::{assets::dialogues::{Dialogue, Stat}, player::Player};
+//!
+//! let dialogue = Dialogue::parse(r#"(
+//!     start: "hi",
+//!     nodes: {
+//!         "hi": (
+//!             lines: ["Hello!"],
+//!             choices: [
+//!                 (text: "Sell wheat", conditions: [AtLeast(Wheat, 10)], effects: [Take(Wheat, 10), Give(Gold, 2)]),
+//!                 (text: "Bye"),
+//!             ],
+//!         ),
+//!     },
+//! )"#).unwrap();
+//!
+//! let mut player = Player::new();
+//! let sell = &dialogue.nodes["hi"].choices[0];
+//! assert!(!sell.available(&player));
+//! player.wheat = 15;
+//! assert!(sell.available(&player));
+//! assert_eq!(Stat::Wheat.get(&player), 15);
+//!
+//! // all nodes must exist
+//! assert!(Dialogue::parse(r#"(start: "hello", nodes: {})"#).is_err());
+//! assert!(Dialogue::load("farmer").is_some());
+//! ```
+
+use std::{collections::BTreeMap, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{assets::items::ItemKind, game::GameRng, player::Player, things::ItemData};
+
+/// Error of dialogue source
+#[derive(Debug)]
+pub enum DialogueError {
+    Parse(ron::error::SpannedError),
+    /// Start or next node of choice doesn't exist
+    UnknownNode(String),
+}
+
+impl fmt::Display for DialogueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "bad dialogue: {e}"),
+            Self::UnknownNode(name) => write!(f, "unknown node `{name}`"),
+        }
+    }
+}
+impl std::error::Error for DialogueError {}
+
+impl From<ron::error::SpannedError> for DialogueError {
+    fn from(value: ron::error::SpannedError) -> Self {
+        Self::Parse(value)
+    }
+}
+
+/// Dialogue tree
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Dialogue {
+    /// Name of the first node
+    pub start: String,
+    pub nodes: BTreeMap<String, Node>,
+}
+
+/// One step of dialogue
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Node {
+    /// Lines said by NPC
+    pub lines: Vec<String>,
+    pub choices: Vec<Choice>,
+}
+
+/// Player's answer
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Choice {
+    pub text: String,
+    /// All conditions must be true to choose it
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+    #[serde(default)]
+    pub effects: Vec<Effect>,
+    /// Name of the next node. Dialogue ends if `None`
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+/// Numeric field of [`Player`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Stat {
+    Health,
+    Hunger,
+    Xp,
+    Gold,
+    Wheat,
+    Water,
+}
+
+/// Condition of [`Choice`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Condition {
+    /// Stat is greater or equal than value
+    AtLeast(Stat, u32),
+    /// Stat is less than value
+    Below(Stat, u32),
+}
+
+/// Effect of [`Choice`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Effect {
+    /// Add value to stat
+    Give(Stat, u32),
+    /// Subtract value from stat (down to zero)
+    Take(Stat, u32),
+    /// Put new item into inventory
+    GiveItem(ItemKind),
+}
+
+impl Stat {
+    /// Get stat of player
+    pub fn get(self, player: &Player) -> u32 {
+        match self {
+            Self::Health => player.health,
+            Self::Hunger => player.hunger,
+            Self::Xp => player.xp,
+            Self::Gold => player.gold,
+            Self::Wheat => player.wheat,
+            Self::Water => player.water,
+        }
+    }
+    /// Get stat of player
+    pub fn get_mut(self, player: &mut Player) -> &mut u32 {
+        match self {
+            Self::Health => &mut player.health,
+            Self::Hunger => &mut player.hunger,
+            Self::Xp => &mut player.xp,
+            Self::Gold => &mut player.gold,
+            Self::Wheat => &mut player.wheat,
+            Self::Water => &mut player.water,
+        }
+    }
+}
+
+impl Condition {
+    /// Check condition
+    pub fn check(self, player: &Player) -> bool {
+        match self {
+            Self::AtLeast(stat, v) => stat.get(player) >= v,
+            Self::Below(stat, v) => stat.get(player) < v,
+        }
+    }
+}
+
+impl Effect {
+    /// Apply effect to player. Returns new item for [`Effect::GiveItem`]. Player is not
+    /// clamped to limits.
+    pub fn apply(self, player: &mut Player, rng: &mut GameRng) -> Option<ItemData> {
+        match self {
+            Self::Give(stat, v) => {
+                let s = stat.get_mut(player);
+                *s = s.saturating_add(v);
+            }
+            Self::Take(stat, v) => {
+                let s = stat.get_mut(player);
+                *s = s.saturating_sub(v);
+            }
+            Self::GiveItem(kind) => return Some(kind.create(rng)),
+        }
+        None
+    }
+}
+
+impl Choice {
+    /// Can player choose it
+    pub fn available(&self, player: &Player) -> bool {
+        self.conditions.iter().all(|c| c.check(player))
+    }
+}
+
+impl Dialogue {
+    /// Embedded dialogue source by its name
+    pub fn source(name: &str) -> Option<&'static str> {
+        match name {
+            "farmer" => Some(include_str!("farmer.ron")),
+            _ => None,
+        }
+    }
+
+    /// Parse dialogue and check that all nodes it refers to exist
+    pub fn parse(src: &str) -> Result<Self, DialogueError> {
+        let dialogue: Self = ron::from_str(src)?;
+        let nexts = dialogue.nodes.values().flat_map(|n| &n.choices);
+        let refs = std::iter::once(&dialogue.start).chain(nexts.filter_map(|c| c.next.as_ref()));
+        for name in refs {
+            if !dialogue.nodes.contains_key(name) {
+                return Err(DialogueError::UnknownNode(name.clone()));
+            }
+        }
+        Ok(dialogue)
+    }
+
+    /// Load embedded dialogue. Panics if it is invalid.
+    pub fn load(name: &str) -> Option<Self> {
+        let src = Self::source(name)?;
+        let dialogue =
+            Self::parse(src).unwrap_or_else(|e| panic!("embedded dialogue `{name}`: {e}"));
+        Some(dialogue)
+    }
+}
+
+/// Dialogue that is shown in lore now
+pub struct ActiveDialogue {
+    pub dialogue: Dialogue,
+    /// Name of current node
+    pub node: String,
+}
+
+impl ActiveDialogue {
+    /// Starts dialogue from the first node
+    pub fn new(dialogue: Dialogue) -> Self {
+        Self {
+            node: dialogue.start.clone(),
+            dialogue,
+        }
+    }
+
+    /// Current node. Panics if dialogue has no such node, it can happen only to dialogue that
+    /// is not made by [`Dialogue::parse`].
+    pub fn current(&self) -> &Node {
+        self.dialogue
+            .nodes
+            .get(&self.node)
+            .unwrap_or_else(|| panic!("dialogue has no node `{}`", self.node))
+    }
+}
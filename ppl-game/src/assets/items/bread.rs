@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 
-use rand::{thread_rng, Rng};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     things::{ItemData, ItemState, ItemTier},
@@ -9,7 +10,10 @@ use crate::{
 
 use super::{ItemBehavior, ItemUpdates};
 
-#[derive(Clone, Debug, PartialEq)]
+/// Mass of bread (in grams) that restores one hunger point
+const MASS_PER_HUNGER: u32 = 5;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Bread {
     Baking {
         /// Tick of baking. Max: 3, min: 0.
@@ -46,6 +50,13 @@ impl ItemBehavior for Bread {
         }
     }
 
+    fn description(&self) -> Cow<'_, str> {
+        match self {
+            Self::Baking { .. } => Cow::Borrowed("Still baking, wait a bit."),
+            Self::Normal { .. } => Cow::Borrowed("Eat it when hungry.\nBigger bread is better."),
+        }
+    }
+
     fn color(&self) -> Color {
         match self {
             Self::Baking { .. } => Color::Normal,
@@ -55,17 +66,30 @@ impl ItemBehavior for Bread {
 
     fn update<UI: crate::ui::Context>(
         &mut self,
-        ctx: crate::things::ItemUpdateContext<UI>,
+        mut ctx: crate::things::ItemUpdateContext<UI>,
     ) -> Result<ItemUpdates, UI::Error> {
         match self {
             Self::Baking { tick: 3 } => {
-                let mass = thread_rng().gen_range(5..=75);
+                let mass = ctx.rng().gen_range(5..=75);
                 *self = Self::Normal { mass };
-                ctx.this.tier = ItemTier::rand(ItemTier::Common..=ItemTier::LevelA);
+                ctx.this.tier = ItemTier::rand(ItemTier::Common..=ItemTier::LevelA, ctx.rng());
             }
             Self::Baking { tick } => *tick += 1,
             _ => {}
         }
         Ok(ItemUpdates::new())
     }
+
+    fn use_item<UI: crate::ui::Context>(
+        &mut self,
+        ctx: crate::things::ItemUpdateContext<UI>,
+    ) -> Result<ItemUpdates, UI::Error> {
+        let Self::Normal { mass } = *self else {
+            return ItemUpdates::new().ok();
+        };
+        let food = u32::from(mass) * ctx.this.tier.bonus_percent() / 100 / MASS_PER_HUNGER;
+        let player = &mut ctx.game_handle.player;
+        player.hunger = player.hunger.saturating_add(food.max(1));
+        ItemUpdates::new().consume().ok()
+    }
 }
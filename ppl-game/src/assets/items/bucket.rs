@@ -0,0 +1,25 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::player::{LimitStat, Modifier};
+
+use super::ItemBehavior;
+
+/// Bigger bucket, raises water limit
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Bucket;
+
+impl ItemBehavior for Bucket {
+    fn name(&self) -> Cow<'_, str> {
+        Cow::Borrowed("Big bucket")
+    }
+
+    fn description(&self) -> Cow<'_, str> {
+        Cow::Borrowed("Carries more water.")
+    }
+
+    fn modifiers(&self) -> Vec<Modifier> {
+        vec![Modifier::flat(LimitStat::Water, 2)]
+    }
+}
@@ -0,0 +1,93 @@
+use std::borrow::Cow;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::registry::{ItemDef, Registry, ThingName},
+    game::GameRng,
+    player::Modifier,
+    things::{ItemData, ItemState, ItemTier, ItemUpdateContext},
+    ui::{self, Color},
+};
+
+use super::{ItemBehavior, ItemUpdates};
+
+/// Item declared in [registry](crate::assets::registry). Item that is not registered
+/// anymore is shown by its name and does nothing.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Custom {
+    name: ThingName,
+    /// Random value from [`ItemDef::value`]
+    value: u8,
+}
+
+impl Custom {
+    /// Creates new item with random tier and value
+    pub fn init(name: ThingName, rng: &mut GameRng) -> ItemData {
+        let (tiers, values) = match Registry::get().item(name) {
+            Some(def) => (def.tiers, def.value),
+            None => ((ItemTier::Common, ItemTier::Common), (0, 0)),
+        };
+        ItemData {
+            state: ItemState {
+                tier: ItemTier::rand(tiers.0..=tiers.1, rng),
+            },
+            item: Self {
+                name,
+                value: rng.gen_range(values.0..=values.1),
+            }
+            .into(),
+        }
+    }
+
+    fn def(&self) -> Option<&'static ItemDef> {
+        Registry::get().item(self.name)
+    }
+}
+
+impl ItemBehavior for Custom {
+    fn name(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.def().map_or(self.name.as_str(), |d| &d.name))
+    }
+
+    fn meta(&self) -> Cow<'_, str> {
+        match self.def() {
+            Some(def) if def.meta.contains("{value}") => {
+                Cow::Owned(def.meta.replace("{value}", &self.value.to_string()))
+            }
+            Some(def) => Cow::Borrowed(&def.meta),
+            None => Cow::Borrowed(""),
+        }
+    }
+
+    fn description(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.def().map_or("", |d| &d.description))
+    }
+
+    fn color(&self) -> Color {
+        self.def().map_or(Color::Disabled, |d| d.color)
+    }
+
+    fn modifiers(&self) -> Vec<Modifier> {
+        self.def().map_or_else(Vec::new, |d| d.modifiers.clone())
+    }
+
+    fn use_item<UI: ui::Context>(
+        &mut self,
+        ctx: ItemUpdateContext<UI>,
+    ) -> Result<ItemUpdates, UI::Error> {
+        let Some(def) = self.def().filter(|d| !d.effects.is_empty()) else {
+            return ItemUpdates::new().ok();
+        };
+        let mut updates = ItemUpdates::new().consume();
+        // player is clamped to limits by caller
+        let handle = ctx.game_handle;
+        for effect in &def.effects {
+            if let Some(item) = effect.apply(&mut handle.player, &mut handle.rng) {
+                updates = updates.give(item);
+            }
+        }
+        updates.ok()
+    }
+}
@@ -1,20 +1,81 @@
 use std::borrow::Cow;
 
 use enum_dispatch::enum_dispatch;
+use serde::{Deserialize, Serialize};
 
-use crate::{things::ItemUpdateContext, ui};
+use crate::{
+    assets::registry::ThingName,
+    game::GameRng,
+    player::Modifier,
+    things::{ItemData, ItemState, ItemTier, ItemUpdateContext},
+    ui,
+};
 
 pub mod bread;
 use bread::Bread;
+pub mod bucket;
+use bucket::Bucket;
+pub mod sack;
+use sack::Sack;
+pub mod custom;
+use custom::Custom;
 
-/// Updates made by item update
+/// Kind of item, used to create new items from data (like dialogues)
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum ItemKind {
+    Bread,
+    Sack,
+    Bucket,
+    /// Item declared in [registry](crate::assets::registry)
+    Custom(ThingName),
+}
+
+impl ItemKind {
+    /// Creates new item
+    pub fn create(self, rng: &mut GameRng) -> ItemData {
+        let mut tier = || ItemState {
+            tier: ItemTier::rand(ItemTier::Common..=ItemTier::LevelA, rng),
+        };
+        match self {
+            Self::Bread => Bread::init(),
+            Self::Sack => ItemData {
+                state: tier(),
+                item: Sack.into(),
+            },
+            Self::Bucket => ItemData {
+                state: tier(),
+                item: Bucket.into(),
+            },
+            Self::Custom(name) => Custom::init(name, rng),
+        }
+    }
+}
+
+/// Updates made by item update or use
 #[derive(Default)]
-pub struct ItemUpdates;
+pub struct ItemUpdates {
+    /// Item is used up and should be removed from inventory
+    pub consumed: bool,
+    /// New items put into inventory after use
+    pub given: Vec<ItemData>,
+}
 
 impl ItemUpdates {
     /// Creates new empty update
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Builder function, marks item as used up
+    pub fn consume(mut self) -> Self {
+        self.consumed = true;
+        self
+    }
+
+    /// Builder function, puts new item into inventory
+    pub fn give(mut self, item: ItemData) -> Self {
+        self.given.push(item);
+        self
     }
 
     /// Wraps into [`Result`]
@@ -34,11 +95,22 @@ pub trait ItemBehavior {
         Cow::Borrowed("")
     }
 
+    /// Item description, shown when item is inspected. May contain line breaks.
+    fn description(&self) -> Cow<'_, str> {
+        Cow::Borrowed("")
+    }
+
     /// Item color.
     fn color(&self) -> ui::Color {
         ui::Color::Normal
     }
 
+    /// Modifiers of player limits while item is in inventory. They are scaled by
+    /// [`ItemTier`] of item.
+    fn modifiers(&self) -> Vec<Modifier> {
+        Vec::new()
+    }
+
     /// Update item. Called every random tick
     fn update<UI: ui::Context>(
         &mut self,
@@ -46,9 +118,22 @@ pub trait ItemBehavior {
     ) -> Result<ItemUpdates, UI::Error> {
         ItemUpdates::new().ok()
     }
+
+    /// Use item. Called when player uses item from inventory. Return
+    /// [`ItemUpdates::consume`] to remove item.
+    fn use_item<UI: ui::Context>(
+        &mut self,
+        ctx: ItemUpdateContext<UI>,
+    ) -> Result<ItemUpdates, UI::Error> {
+        ItemUpdates::new().ok()
+    }
 }
 
 #[enum_dispatch(ItemBehavior)]
+#[derive(Serialize, Deserialize)]
 pub enum Item {
     Bread,
+    Sack,
+    Bucket,
+    Custom,
 }
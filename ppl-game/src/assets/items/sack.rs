@@ -0,0 +1,25 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::player::{LimitStat, Modifier};
+
+use super::ItemBehavior;
+
+/// Sack, raises wheat limit
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Sack;
+
+impl ItemBehavior for Sack {
+    fn name(&self) -> Cow<'_, str> {
+        Cow::Borrowed("Sack")
+    }
+
+    fn description(&self) -> Cow<'_, str> {
+        Cow::Borrowed("Holds more wheat.")
+    }
+
+    fn modifiers(&self) -> Vec<Modifier> {
+        vec![Modifier::percent(LimitStat::Wheat, 50)]
+    }
+}
@@ -0,0 +1,457 @@
+//! # Text map format
+//!
+//! Map is a plain text file with sections: `[legend]`, `[exits]` (optional) and `[map]`.
+//!
+//! Legend maps one glyph to block type, collision type and behavior:
+//! `<glyph> = <BlockTy> <CollisionTy> <behavior>`. Behaviors are `generic`, `wheat`, `water`
+//! and `npc(<dialogue>)`. Block declared in [registry](crate::assets::registry) is used by
+//! name alone, its definition has the rest: `<glyph> = <name>`.
+//!
+//! Exits lead to other maps. `door <x>,<y> -> <map> <x>,<y>` moves player who steps onto
+//! `(x; y)` to the entry point of other map. `edge <side> -> <map> [<x>,<y>]` does the same
+//! when player leaves the map through `up`, `down`, `left` or `right` side. Without entry
+//! point player appears on the opposite side of the other map.
+//!
+//! Map section is an ASCII grid, one line per Y. Spaces and dots are air (no block).
+//! It lasts until the end of file, so it must be the last section. Map is as wide as its
+//! longest line, it can't be larger than [`MAX_SIZE`](crate::map::grid::MAX_SIZE).
+//! Lines starting with `//` outside of map section are comments.
+//!
+//! # Example
+//! ```
+//! use ppl_game::{assets::{maps::format, registry::ThingName}, ui::{BlockTy, Point}};
+//!
+//! let map = format::parse("\
+//! [legend]
+//! X = Wall Collision generic
+//! W = Wheat CanUse wheat
+//! * = BerryBush
+//!
+//! [exits]
+//! edge right -> forest
+//!
+//! [map]
+//! X.W
+//! .X*
+//! ").unwrap();
+//! assert_eq!(map.size, Point(3, 2));
+//! assert_eq!(map.blocks.len(), 4);
+//! assert_eq!(map.blocks.get(Point(2, 0)).unwrap().state.ty, BlockTy::Wheat);
+//! let bush = BlockTy::Custom(ThingName::new("BerryBush"));
+//! assert_eq!(map.blocks.get(Point(2, 1)).unwrap().state.ty, bush);
+//! assert_eq!(map.exits.len(), 1);
+//!
+//! let err = format::parse("[legend]\n[map]\n..?").err().unwrap();
+//! assert_eq!((err.line, err.column), (3, 3));
+//! ```
+
+use std::{collections::HashMap, fmt, io, path::PathBuf};
+
+use crate::{
+    assets::{
+        blocks::{crop::Crop, generic::Generic, npc::Npc, water::Water, wheat::Wheat, Block},
+        dialogues::Dialogue,
+        maps::GameMap,
+        registry::{BehaviorDef, Registry, ThingName},
+    },
+    map::{grid::MAX_SIZE, ExitTrigger, MapData, MapExit, Side},
+    things::{BlockData, CollisionTy},
+    ui::{BlockTy, Point},
+};
+
+/// Error with position in map source. Line and column start from 1.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+/// Kind of [`ParseError`]
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// Glyph in map is not declared in legend
+    UnknownGlyph(char),
+    /// Glyph declared in legend twice
+    DuplicateGlyph(char),
+    /// Legend line is not `<glyph> = <BlockTy> <CollisionTy> <behavior>` or
+    /// `<glyph> = <name>`
+    BadLegend,
+    UnknownBlockTy(String),
+    UnknownCollision(String),
+    UnknownBehavior(String),
+    /// Exit line is not `door <x>,<y> -> <map> <x>,<y>` or `edge <side> -> <map> [<x>,<y>]`
+    BadExit,
+    /// Point is not `<x>,<y>`
+    BadPoint(String),
+    /// Side is not `up`, `down`, `left` or `right`
+    UnknownSide(String),
+    UnknownMap(String),
+    /// Section header is not `[legend]`, `[exits]` or `[map]`
+    UnknownSection(String),
+    /// Line outside of any section
+    NoSection,
+    /// Map does not fit into [`Point`]
+    TooLarge,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ParseErrorKind::*;
+        write!(f, "{}:{}: ", self.line, self.column)?;
+        match &self.kind {
+            UnknownGlyph(c) => write!(f, "glyph `{c}` is not in legend"),
+            DuplicateGlyph(c) => write!(f, "glyph `{c}` is already in legend"),
+            BadLegend => write!(f, "expected `<glyph> = <block> <collision> <behavior>`"),
+            UnknownBlockTy(s) => write!(f, "unknown block type `{s}`"),
+            UnknownCollision(s) => write!(f, "unknown collision type `{s}`"),
+            UnknownBehavior(s) => write!(f, "unknown behavior `{s}`"),
+            BadExit => write!(
+                f,
+                "expected `door <x>,<y> -> <map> <x>,<y>` or `edge <side> -> <map> [<x>,<y>]`"
+            ),
+            BadPoint(s) => write!(f, "expected `<x>,<y>`, found `{s}`"),
+            UnknownSide(s) => write!(f, "unknown side `{s}`"),
+            UnknownMap(s) => write!(f, "unknown map `{s}`"),
+            UnknownSection(s) => write!(f, "unknown section `[{s}]`"),
+            NoSection => write!(f, "expected `[legend]`, `[exits]` or `[map]` section"),
+            TooLarge => write!(f, "map is too large"),
+        }
+    }
+}
+impl std::error::Error for ParseError {}
+
+/// Error while loading map
+#[derive(Debug)]
+pub enum MapError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "can't read map: {e}"),
+            Self::Parse(e) => write!(f, "bad map: {e}"),
+        }
+    }
+}
+impl std::error::Error for MapError {}
+
+impl From<io::Error> for MapError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+impl From<ParseError> for MapError {
+    fn from(value: ParseError) -> Self {
+        Self::Parse(value)
+    }
+}
+
+/// Where map is loaded from
+pub enum MapSource {
+    /// Map source embedded into binary with [`include_str!`]
+    Embedded(&'static str),
+    /// Map file
+    Path(PathBuf),
+}
+
+impl MapSource {
+    /// Read and parse map
+    pub fn load(&self) -> Result<MapData, MapError> {
+        match self {
+            Self::Embedded(src) => Ok(parse(src)?),
+            Self::Path(path) => Ok(parse(&std::fs::read_to_string(path)?)?),
+        }
+    }
+}
+
+/// Block behavior constructor from legend
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Behavior {
+    Generic,
+    Wheat,
+    Water,
+    /// NPC with dialogue name
+    Npc(String),
+    /// Crop with name of ripe block
+    Crop(ThingName),
+}
+
+impl Behavior {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "generic" => Some(Self::Generic),
+            "wheat" => Some(Self::Wheat),
+            "water" => Some(Self::Water),
+            _ => {
+                let dialogue = name.strip_prefix("npc(")?.strip_suffix(')')?;
+                Dialogue::source(dialogue)?;
+                Some(Self::Npc(dialogue.to_owned()))
+            }
+        }
+    }
+
+    /// Creates new block behavior
+    pub fn build(&self) -> Block {
+        match self {
+            Self::Generic => Generic.into(),
+            Self::Wheat => Wheat::new().into(),
+            Self::Water => Water.into(),
+            Self::Npc(dialogue) => Npc::new(dialogue)
+                .expect("dialogue is checked by parser")
+                .into(),
+            Self::Crop(block) => Crop::new(*block).into(),
+        }
+    }
+}
+
+/// Legend entry, everything to create [`BlockData`] except position
+struct LegendEntry {
+    ty: BlockTy,
+    collision: CollisionTy,
+    behavior: Behavior,
+}
+
+/// Built-in block type by name
+pub(crate) fn block_ty(name: &str) -> Option<BlockTy> {
+    use BlockTy::*;
+    Some(match name {
+        "Air" => Air,
+        "Player" => Player,
+        "OtherPlayer" => OtherPlayer,
+        "NPCFarmer" => NPCFarmer,
+        "Wheat" => Wheat,
+        "GrowingWheat" => GrowingWheat,
+        "DryWheat" => DryWheat,
+        "Tree" => Tree,
+        "Wall" => Wall,
+        "WallDoor" => WallDoor,
+        "Water" => Water,
+        "BridgeV" => BridgeV,
+        "BridgeH" => BridgeH,
+        _ => return None,
+    })
+}
+
+fn collision_ty(name: &str) -> Option<CollisionTy> {
+    use CollisionTy::*;
+    Some(match name {
+        "NoCollision" => NoCollision,
+        "CanUse" => CanUse,
+        "UseNearby" => UseNearby,
+        "Collision" => Collision,
+        _ => return None,
+    })
+}
+
+/// Column (from 1) of byte index in line
+fn column(line: &str, byte_idx: usize) -> usize {
+    line[..byte_idx].chars().count() + 1
+}
+
+/// Splits line into words with their columns
+fn words(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split_whitespace().map(move |word| {
+        // `word` is a subslice of `line`
+        (
+            column(line, word.as_ptr() as usize - line.as_ptr() as usize),
+            word,
+        )
+    })
+}
+
+/// Parse `<x>,<y>`
+fn point(word: &str) -> Option<Point> {
+    let (x, y) = word.split_once(',')?;
+    Some(Point(x.parse().ok()?, y.parse().ok()?))
+}
+
+enum Section {
+    None,
+    Legend,
+    Exits,
+    Map { y: u16 },
+}
+
+/// Parse map from source. See [module docs](self) for format.
+pub fn parse(src: &str) -> Result<MapData, ParseError> {
+    let mut legend = HashMap::new();
+    let mut map = MapData::default();
+    let mut section = Section::None;
+
+    for (line_no, line) in src.lines().enumerate() {
+        let line_no = line_no + 1;
+        let err = |column, kind| ParseError {
+            line: line_no,
+            column,
+            kind,
+        };
+
+        if let Section::Map { y } = &mut section {
+            let width = line.chars().count();
+            let width = u16::try_from(width)
+                .ok()
+                .filter(|&w| w <= MAX_SIZE.0)
+                .ok_or_else(|| err(width, ParseErrorKind::TooLarge))?;
+            if *y >= MAX_SIZE.1 {
+                return Err(err(1, ParseErrorKind::TooLarge));
+            }
+            for (x, glyph) in line.chars().enumerate() {
+                if glyph == ' ' || glyph == '.' {
+                    continue;
+                }
+                let entry: &LegendEntry = legend
+                    .get(&glyph)
+                    .ok_or_else(|| err(x + 1, ParseErrorKind::UnknownGlyph(glyph)))?;
+                let block = BlockData::new(
+                    Point(x as u16, *y),
+                    entry.collision,
+                    entry.ty,
+                    entry.behavior.build(),
+                );
+                map.blocks
+                    .insert(block)
+                    .map_err(|_| err(x + 1, ParseErrorKind::TooLarge))?;
+            }
+            *y += 1;
+            map.size = Point(map.size.0.max(width), *y);
+            continue;
+        }
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+        if let Some(name) = trimmed.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            section = match name {
+                "legend" => Section::Legend,
+                "exits" => Section::Exits,
+                "map" => Section::Map { y: 0 },
+                _ => return Err(err(1, ParseErrorKind::UnknownSection(name.to_owned()))),
+            };
+            continue;
+        }
+
+        match section {
+            Section::None => return Err(err(1, ParseErrorKind::NoSection)),
+            Section::Legend => {
+                let (glyph_column, glyph, entry) =
+                    parse_legend(line).map_err(|(c, k)| err(c, k))?;
+                if glyph == ' ' || glyph == '.' || legend.insert(glyph, entry).is_some() {
+                    return Err(err(glyph_column, ParseErrorKind::DuplicateGlyph(glyph)));
+                }
+            }
+            Section::Exits => map
+                .exits
+                .push(parse_exit(line).map_err(|(c, k)| err(c, k))?),
+            Section::Map { .. } => unreachable!("map section is parsed above"),
+        }
+    }
+
+    Ok(map)
+}
+
+/// Parse `<glyph> = <BlockTy> <CollisionTy> <behavior>`. Returns glyph with its column.
+fn parse_legend(line: &str) -> Result<(usize, char, LegendEntry), (usize, ParseErrorKind)> {
+    let Some((glyph_at, glyph)) = line.char_indices().find(|(_, c)| !c.is_whitespace()) else {
+        unreachable!("line is not empty");
+    };
+    let glyph_column = column(line, glyph_at);
+    let rest = &line[glyph_at + glyph.len_utf8()..];
+    if !rest.trim_start().starts_with('=') {
+        return Err((glyph_column + 1, ParseErrorKind::BadLegend));
+    }
+    let mut words = words(line).skip(2);
+    let (Some(ty), collision, behavior, None) =
+        (words.next(), words.next(), words.next(), words.next())
+    else {
+        return Err((glyph_column, ParseErrorKind::BadLegend));
+    };
+    let (Some(collision), Some(behavior)) = (collision, behavior) else {
+        if collision.is_some() {
+            return Err((glyph_column, ParseErrorKind::BadLegend));
+        }
+        let entry = registered(ty.1)
+            .ok_or_else(|| (ty.0, ParseErrorKind::UnknownBlockTy(ty.1.to_owned())))?;
+        return Ok((glyph_column, glyph, entry));
+    };
+
+    let entry = LegendEntry {
+        ty: block_ty(ty.1)
+            .ok_or_else(|| (ty.0, ParseErrorKind::UnknownBlockTy(ty.1.to_owned())))?,
+        collision: collision_ty(collision.1).ok_or_else(|| {
+            (
+                collision.0,
+                ParseErrorKind::UnknownCollision(collision.1.to_owned()),
+            )
+        })?,
+        behavior: Behavior::from_name(behavior.1).ok_or_else(|| {
+            (
+                behavior.0,
+                ParseErrorKind::UnknownBehavior(behavior.1.to_owned()),
+            )
+        })?,
+    };
+    Ok((glyph_column, glyph, entry))
+}
+
+/// Legend entry of block declared in registry
+fn registered(name: &str) -> Option<LegendEntry> {
+    let name = ThingName::new(name);
+    let def = Registry::get().block(name)?;
+    let behavior = match &def.behavior {
+        BehaviorDef::Generic => Behavior::Generic,
+        BehaviorDef::Water => Behavior::Water,
+        BehaviorDef::Npc(dialogue) => Behavior::Npc(dialogue.clone()),
+        BehaviorDef::Crop { .. } => Behavior::Crop(name),
+    };
+    Some(LegendEntry {
+        ty: BlockTy::Custom(name),
+        collision: def.collision,
+        behavior,
+    })
+}
+
+/// Parse `door <x>,<y> -> <map> <x>,<y>` or `edge <side> -> <map> [<x>,<y>]`
+fn parse_exit(line: &str) -> Result<MapExit, (usize, ParseErrorKind)> {
+    let mut words = words(line);
+    let (Some(kind), Some(at), Some(arrow), Some(to)) =
+        (words.next(), words.next(), words.next(), words.next())
+    else {
+        return Err((1, ParseErrorKind::BadExit));
+    };
+    let entry = words.next();
+    if arrow.1 != "->" || words.next().is_some() {
+        return Err((arrow.0, ParseErrorKind::BadExit));
+    }
+
+    let bad_point =
+        |(column, word): (usize, &str)| (column, ParseErrorKind::BadPoint(word.to_owned()));
+    let trigger = match kind.1 {
+        "door" => ExitTrigger::At(point(at.1).ok_or_else(|| bad_point(at))?),
+        "edge" => ExitTrigger::Edge(match at.1 {
+            "up" => Side::Up,
+            "down" => Side::Down,
+            "left" => Side::Left,
+            "right" => Side::Right,
+            _ => return Err((at.0, ParseErrorKind::UnknownSide(at.1.to_owned()))),
+        }),
+        _ => return Err((kind.0, ParseErrorKind::BadExit)),
+    };
+    let entry = match entry {
+        Some(word) => Some(point(word.1).ok_or_else(|| bad_point(word))?),
+        None if matches!(trigger, ExitTrigger::At(_)) => {
+            return Err((to.0 + to.1.len(), ParseErrorKind::BadExit))
+        }
+        None => None,
+    };
+
+    Ok(MapExit {
+        trigger,
+        to: GameMap::from_name(to.1)
+            .ok_or_else(|| (to.0, ParseErrorKind::UnknownMap(to.1.to_owned())))?,
+        entry,
+    })
+}
@@ -1,16 +1,54 @@
-use crate::things::BlockData;
+use serde::{Deserialize, Serialize};
 
-pub mod farm;
+use crate::map::MapData;
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub mod format;
+use format::MapSource;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
 pub enum GameMap {
     #[default]
     Farm,
+    Village,
+    Forest,
 }
 impl GameMap {
-    pub fn init(self) -> Vec<BlockData> {
+    /// Get map by its name in map files (`farm`, `village`, ...)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "farm" => Some(GameMap::Farm),
+            "village" => Some(GameMap::Village),
+            "forest" => Some(GameMap::Forest),
+            _ => None,
+        }
+    }
+
+    /// Map source embedded into binary
+    pub fn source(self) -> MapSource {
         match self {
-            GameMap::Farm => farm::init(),
+            GameMap::Farm => MapSource::Embedded(include_str!("farm.map")),
+            GameMap::Village => MapSource::Embedded(include_str!("village.map")),
+            GameMap::Forest => MapSource::Embedded(include_str!("forest.map")),
         }
     }
+
+    /// Load embedded map. Panics if it is invalid, to load map from file use [`MapSource`].
+    ///
+    /// Entries of exits between embedded maps are free:
+    /// ```
+    /// use ppl_game::assets::maps::GameMap;
+    ///
+    /// for map in [GameMap::Farm, GameMap::Village, GameMap::Forest] {
+    ///     for exit in map.init().exits {
+    ///         if let Some(entry) = exit.entry {
+    ///             assert!(exit.to.init().can_stand(entry), "{map:?} -> {exit:?}");
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn init(self) -> MapData {
+        self.source()
+            .load()
+            .unwrap_or_else(|e| panic!("embedded map {self:?}: {e}"))
+    }
 }
@@ -2,7 +2,9 @@
 
 /// Game blocks
 pub mod blocks;
+pub mod dialogues;
 /// Game items
 pub mod items;
 /// Game maps
 pub mod maps;
+pub mod registry;
@@ -0,0 +1,363 @@
+//! # Data-driven blocks and items
+//!
+//! Blocks and items that need no code of their own are declared in [RON](https://docs.rs/ron)
+//! definitions: `blocks` map block names to [`BlockDef`]s, `items` map item names to
+//! [`ItemDef`]s. Embedded definitions are in `things.ron` of this directory, more can be
+//! loaded with [`Registry::load`] and made used by [`Registry::install`] before the game
+//! starts.
+//!
+//! Registered block is [`BlockTy::Custom`], map legend uses it by name alone:
+//! `<glyph> = <name>`, see [`crate::assets::maps::format`]. Registered item is
+//! [`ItemKind::Custom`], e.g. dialogues give it with `GiveItem(Custom("<name>"))`. Saves keep
+//! names, so definitions may be added and reordered between games. Recordings are replayed
+//! the same way only with the same definitions.
+//!
+//! # Example
+//! ```
+//! use ppl_game::{
+//!     assets::registry::{Registry, RegistryError, ThingName},
+//!     ui::Color,
+//! };
+//!
+//! let registry = Registry::parse(r#"(
+//!     blocks: {
+//!         "Pumpkin": (
+//!             glyph: 'o',
+//!             color: WheatBlock,
+//!             collision: CanUse,
+//!             behavior: Crop(grow_ticks: 10, growing: Custom("Sprout"), drops: [(item: Custom("Pumpkin"))]),
+//!         ),
+//!         "Sprout": (glyph: ',', color: GrowingWheatBlock),
+//!     },
+//!     items: {
+//!         "Pumpkin": (name: "Pumpkin", meta: "{value}kg", value: (2, 9), effects: [Give(Hunger, 5)]),
+//!     },
+//! )"#).unwrap();
+//! let pumpkin = ThingName::new("Pumpkin");
+//! assert_eq!(registry.block(pumpkin).unwrap().glyph, 'o');
+//! assert_eq!(registry.item(pumpkin).unwrap().color, Color::Normal);
+//!
+//! // references are checked
+//! let err = Registry::parse(r#"(blocks: { "Wall": (glyph: 'X') })"#).err().unwrap();
+//! assert!(matches!(err, RegistryError::BuiltinName(_)));
+//! let err = Registry::parse(r#"(items: { "Box": (name: "Box", effects: [GiveItem(Custom("Gift"))]) })"#);
+//! assert!(matches!(err, Err(RegistryError::UnknownItem { .. })));
+//! ```
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt, io,
+    path::Path,
+    sync::{Mutex, OnceLock, PoisonError},
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    assets::{
+        dialogues::{Dialogue, Effect},
+        items::ItemKind,
+        maps::format,
+    },
+    player::Modifier,
+    things::{CollisionTy, ItemTier},
+    ui::{BlockTy, Color},
+};
+
+/// Name of registered block or item. Names are interned, so it's `Copy` and cheap to
+/// compare.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ThingName(&'static str);
+
+impl ThingName {
+    pub fn new(name: &str) -> Self {
+        static NAMES: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+        let mut names = NAMES
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(name) = names.get(name) {
+            return Self(name);
+        }
+        let name: &'static str = Box::leak(name.into());
+        names.insert(name);
+        Self(name)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        self.0
+    }
+}
+
+impl fmt::Debug for ThingName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.0, f)
+    }
+}
+impl fmt::Display for ThingName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl Serialize for ThingName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+impl<'de> Deserialize<'de> for ThingName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::new(&String::deserialize(deserializer)?))
+    }
+}
+
+/// Block definition
+#[derive(Clone, Debug, Deserialize)]
+pub struct BlockDef {
+    /// Char that represents block in text interfaces
+    pub glyph: char,
+    #[serde(default)]
+    pub color: Color,
+    #[serde(default = "no_collision")]
+    pub collision: CollisionTy,
+    #[serde(default)]
+    pub behavior: BehaviorDef,
+}
+
+fn no_collision() -> CollisionTy {
+    CollisionTy::NoCollision
+}
+
+/// Behavior of registered block
+#[derive(Clone, Debug, Default, Deserialize)]
+pub enum BehaviorDef {
+    /// Does nothing
+    #[default]
+    Generic,
+    /// Fills player's bucket like [`BlockTy::Water`]
+    Water,
+    /// Talks with player, has dialogue name
+    Npc(String),
+    /// Grows and gives drops and effects when ripe one is used, see
+    /// [`Crop`](crate::assets::blocks::crop::Crop)
+    Crop {
+        /// Random ticks from harvest to ripe crop
+        grow_ticks: u8,
+        /// Block shown while crop grows
+        growing: BlockTy,
+        #[serde(default)]
+        drops: Vec<CropDrop>,
+        /// Effects of harvest
+        #[serde(default)]
+        effects: Vec<Effect>,
+    },
+}
+
+/// Item that may be given by harvest
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct CropDrop {
+    pub item: ItemKind,
+    /// Chance in percent
+    #[serde(default = "always")]
+    pub chance: u8,
+}
+
+fn always() -> u8 {
+    100
+}
+
+/// Item definition
+#[derive(Clone, Debug, Deserialize)]
+pub struct ItemDef {
+    pub name: String,
+    #[serde(default)]
+    pub color: Color,
+    /// Shown after name, `{value}` is replaced by value of item
+    #[serde(default)]
+    pub meta: String,
+    #[serde(default)]
+    pub description: String,
+    /// Lowest and highest tier of new item
+    #[serde(default = "common_tiers")]
+    pub tiers: (ItemTier, ItemTier),
+    /// Lowest and highest random value of new item, see `meta`
+    #[serde(default)]
+    pub value: (u8, u8),
+    /// Modifiers while item is in inventory, scaled by tier
+    #[serde(default)]
+    pub modifiers: Vec<Modifier>,
+    /// Effects of using item. Item with effects is used up.
+    #[serde(default)]
+    pub effects: Vec<Effect>,
+}
+
+fn common_tiers() -> (ItemTier, ItemTier) {
+    (ItemTier::Common, ItemTier::Common)
+}
+
+/// Error while loading definitions
+#[derive(Debug)]
+pub enum RegistryError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    /// Block has name of built-in [`BlockTy`]
+    BuiltinName(ThingName),
+    /// Definition `by` refers to block that is not registered
+    UnknownBlock {
+        by: ThingName,
+        name: ThingName,
+    },
+    /// Definition `by` refers to item that is not registered
+    UnknownItem {
+        by: ThingName,
+        name: ThingName,
+    },
+    /// Definition `by` refers to dialogue that doesn't exist
+    UnknownDialogue {
+        by: ThingName,
+        name: String,
+    },
+    /// Lowest tier or value of item is higher than highest one
+    BadRange(ThingName),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "can't read definitions: {e}"),
+            Self::Parse(e) => write!(f, "bad definitions: {e}"),
+            Self::BuiltinName(name) => write!(f, "block `{name}` is built-in"),
+            Self::UnknownBlock { by, name } => write!(f, "`{by}`: unknown block `{name}`"),
+            Self::UnknownItem { by, name } => write!(f, "`{by}`: unknown item `{name}`"),
+            Self::UnknownDialogue { by, name } => write!(f, "`{by}`: unknown dialogue `{name}`"),
+            Self::BadRange(name) => write!(f, "`{name}`: range is reversed"),
+        }
+    }
+}
+impl std::error::Error for RegistryError {}
+
+impl From<io::Error> for RegistryError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+impl From<ron::error::SpannedError> for RegistryError {
+    fn from(value: ron::error::SpannedError) -> Self {
+        Self::Parse(value)
+    }
+}
+
+/// Definitions of blocks and items, see [module docs](self)
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Registry {
+    #[serde(default)]
+    pub blocks: BTreeMap<ThingName, BlockDef>,
+    #[serde(default)]
+    pub items: BTreeMap<ThingName, ItemDef>,
+}
+
+/// Registry used by the game
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+impl Registry {
+    /// Source of embedded definitions
+    pub const EMBEDDED: &'static str = include_str!("things.ron");
+
+    /// Parse and check definitions
+    pub fn parse(src: &str) -> Result<Self, RegistryError> {
+        let registry: Self = ron::from_str(src)?;
+        registry.check()?;
+        Ok(registry)
+    }
+
+    /// Embedded definitions with ones from file. File replaces embedded definitions with
+    /// same names.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RegistryError> {
+        let file: Self = ron::from_str(&std::fs::read_to_string(path)?)?;
+        let mut registry: Self = ron::from_str(Self::EMBEDDED)?;
+        registry.blocks.extend(file.blocks);
+        registry.items.extend(file.items);
+        registry.check()?;
+        Ok(registry)
+    }
+
+    /// Registry used by the game. Embedded one if nothing was installed. Panics if embedded
+    /// definitions are invalid.
+    pub fn get() -> &'static Self {
+        REGISTRY.get_or_init(|| {
+            Self::parse(Self::EMBEDDED).unwrap_or_else(|e| panic!("embedded definitions: {e}"))
+        })
+    }
+
+    /// Makes registry used by the game. It must be done before the game is created: if
+    /// registry is already used, `self` is given back.
+    pub fn install(self) -> Result<(), Self> {
+        REGISTRY.set(self)
+    }
+
+    /// Block definition by name
+    pub fn block(&self, name: ThingName) -> Option<&BlockDef> {
+        self.blocks.get(&name)
+    }
+
+    /// Item definition by name
+    pub fn item(&self, name: ThingName) -> Option<&ItemDef> {
+        self.items.get(&name)
+    }
+
+    /// Checks that all references are valid
+    fn check(&self) -> Result<(), RegistryError> {
+        let block = |by, ty| match ty {
+            BlockTy::Custom(name) if self.block(name).is_none() => {
+                Err(RegistryError::UnknownBlock { by, name })
+            }
+            _ => Ok(()),
+        };
+        let item = |by, kind| match kind {
+            ItemKind::Custom(name) if self.item(name).is_none() => {
+                Err(RegistryError::UnknownItem { by, name })
+            }
+            _ => Ok(()),
+        };
+        let effects = |by, effects: &[Effect]| {
+            effects.iter().try_for_each(|e| match *e {
+                Effect::GiveItem(kind) => item(by, kind),
+                _ => Ok(()),
+            })
+        };
+
+        for (&name, def) in &self.blocks {
+            if format::block_ty(name.as_str()).is_some() {
+                return Err(RegistryError::BuiltinName(name));
+            }
+            match &def.behavior {
+                BehaviorDef::Generic | BehaviorDef::Water => {}
+                BehaviorDef::Npc(dialogue) => {
+                    if Dialogue::source(dialogue).is_none() {
+                        return Err(RegistryError::UnknownDialogue {
+                            by: name,
+                            name: dialogue.clone(),
+                        });
+                    }
+                }
+                BehaviorDef::Crop {
+                    growing,
+                    drops,
+                    effects: harvest,
+                    ..
+                } => {
+                    block(name, *growing)?;
+                    drops.iter().try_for_each(|d| item(name, d.item))?;
+                    effects(name, harvest)?;
+                }
+            }
+        }
+        for (&name, def) in &self.items {
+            if def.tiers.0 > def.tiers.1 || def.value.0 > def.value.1 {
+                return Err(RegistryError::BadRange(name));
+            }
+            effects(name, &def.effects)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+//! # Camera
+//!
+//! Map can be bigger than main fragment. [`Camera`] shows part of the map around player and
+//! translates map points to screen points.
+//!
+//! # Example
+//! ```
+//! use ppl_game::{camera::Camera, ui::Point};
+//!
+//! let map = Point(100, 50);
+//! let mut camera = Camera::new(Point(30, 12));
+//!
+//! // player near top left corner, nothing to scroll
+//! assert!(!camera.follow(Point(5, 5), map));
+//! assert_eq!(camera.to_screen(Point(5, 5)), Some(Point(5, 5)));
+//!
+//! // player went right, camera keeps margin to the edge
+//! assert!(camera.follow(Point(40, 5), map));
+//! assert_eq!(camera.origin, Point(14, 0));
+//! assert_eq!(camera.to_screen(Point(40, 5)), Some(Point(26, 5)));
+//! assert_eq!(camera.to_screen(Point(5, 5)), None);
+//!
+//! // camera doesn't go out of map
+//! camera.follow(Point(99, 49), map);
+//! assert_eq!(camera.origin, Point(70, 38));
+//! ```
+
+use crate::ui::Point;
+
+/// Cells kept between player and edge of view when map is bigger than view
+pub const SCROLL_MARGIN: u16 = 3;
+
+/// Part of map shown in main fragment
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Camera {
+    /// Point of map shown in top left corner
+    pub origin: Point,
+    /// Size of view (cols; lines)
+    pub size: Point,
+}
+
+impl Camera {
+    /// Camera in top left corner of map
+    pub fn new(size: Point) -> Self {
+        Self {
+            origin: Point(0, 0),
+            size,
+        }
+    }
+
+    /// Screen point of map point, `None` if it's out of view
+    pub fn to_screen(&self, Point(x, y): Point) -> Option<Point> {
+        let x = x.checked_sub(self.origin.0).filter(|&x| x < self.size.0)?;
+        let y = y.checked_sub(self.origin.1).filter(|&y| y < self.size.1)?;
+        Some(Point(x, y))
+    }
+
+    /// Map point shown at screen point
+    pub fn to_world(&self, Point(x, y): Point) -> Point {
+        Point(
+            self.origin.0.saturating_add(x),
+            self.origin.1.saturating_add(y),
+        )
+    }
+
+    /// Screen points of view line by line
+    pub fn screen_points(&self) -> impl Iterator<Item = Point> {
+        let Point(width, height) = self.size;
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point(x, y)))
+    }
+
+    /// Scrolls to keep `target` [`SCROLL_MARGIN`] away from edges of view, but not out of
+    /// map with `map_size`. Returns `true` if camera moved.
+    pub fn follow(&mut self, target: Point, map_size: Point) -> bool {
+        let origin = Point(
+            follow_axis(self.origin.0, self.size.0, target.0, map_size.0),
+            follow_axis(self.origin.1, self.size.1, target.1, map_size.1),
+        );
+        let moved = origin != self.origin;
+        self.origin = origin;
+        moved
+    }
+}
+
+/// [`Camera::follow`] on one axis
+fn follow_axis(origin: u16, view: u16, target: u16, map: u16) -> u16 {
+    if map <= view {
+        return 0;
+    }
+    let margin = SCROLL_MARGIN.min(view.saturating_sub(1) / 2) as u32;
+    let (origin, view, target) = (origin as u32, view as u32, target as u32);
+    let origin = if target < origin + margin {
+        target.saturating_sub(margin)
+    } else if target + margin >= origin + view {
+        target + margin + 1 - view
+    } else {
+        origin
+    };
+    origin.min(map as u32 - view) as u16
+}
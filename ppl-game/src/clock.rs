@@ -0,0 +1,69 @@
+//! # Game clock
+//!
+//! By default game is turn-based: random tick is done on every player's move. In real-time
+//! mode ticks are done by timer, [`Clock`] tells when. Front-end waits for input no longer
+//! than [`Clock::until_next`] and then does [`Clock::due`] ticks with
+//! [`crate::replay::Input::Tick`].
+//!
+//! # Example
+//! ```
+//! use std::time::{Duration, Instant};
+//! use ppl_game::clock::Clock;
+//!
+//! let start = Instant::now();
+//! let mut clock = Clock::new(Duration::from_millis(100), start);
+//!
+//! assert_eq!(clock.due(start + Duration::from_millis(50)), 0);
+//! assert_eq!(clock.until_next(start + Duration::from_millis(50)), Duration::from_millis(50));
+//! assert_eq!(clock.due(start + Duration::from_millis(250)), 2);
+//! assert_eq!(clock.until_next(start + Duration::from_millis(250)), Duration::from_millis(50));
+//! ```
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Ticks done at once when clock is late, other are skipped
+pub const MAX_CATCH_UP: u32 = 10;
+
+/// When random ticks are done
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum TickMode {
+    /// On every player's move
+    #[default]
+    TurnBased,
+    /// By timer with interval, player's moves don't tick
+    RealTime(Duration),
+}
+
+/// Timer of real-time ticks
+pub struct Clock {
+    interval: Duration,
+    next: Instant,
+}
+
+impl Clock {
+    /// Creates clock with first tick after `interval` from `now`
+    pub fn new(interval: Duration, now: Instant) -> Self {
+        Self {
+            interval,
+            next: now + interval,
+        }
+    }
+
+    /// Time left until next tick, zero if it's due
+    pub fn until_next(&self, now: Instant) -> Duration {
+        self.next.saturating_duration_since(now)
+    }
+
+    /// Number of ticks due at `now` (at most [`MAX_CATCH_UP`]). They are counted as done.
+    pub fn due(&mut self, now: Instant) -> u32 {
+        if now < self.next {
+            return 0;
+        }
+        let interval = self.interval.as_nanos().max(1);
+        let late = ((now - self.next).as_nanos() / interval + 1).min(u32::MAX as u128) as u32;
+        self.next += self.interval * late;
+        late.min(MAX_CATCH_UP)
+    }
+}
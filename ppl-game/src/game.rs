@@ -1,24 +1,44 @@
 //! # Main game state
 
-use rand::{thread_rng, Rng};
+use rand::{thread_rng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    assets::items::ItemBehavior,
-    map::GameMaps,
+    assets::maps::GameMap,
+    assets::{
+        dialogues::{ActiveDialogue, Dialogue},
+        items::ItemBehavior,
+    },
+    camera::Camera,
+    clock::TickMode,
+    map::{ExitTrigger, GameMaps, MapExit, Side},
     player::{Player, PlayerInventory, PlayerLimits},
+    replay::Input,
     things::{BlockData, BlockState, CollisionTy, ItemData, ItemTier, ItemUpdateContext},
     ui::{BlockFragment, BlockTy, Color, Context, Fragment, Point, TextFragment, TextFragmentFmt},
 };
 
-/// Maximum point of map
-pub const MAX_POINT: Point = Point(29, 11);
+/// Items shown on one page of [`LoreContents::Items`]
+pub const ITEMS_PER_PAGE: usize = 9;
+
+/// Chance (in percent) to lose one hunger point on random tick
+const HUNGER_CHANCE: u32 = 5;
+/// Chance (in percent) to heal or (when starving) lose one health point on random tick
+const HEALTH_CHANCE: u32 = 20;
+
+/// Random number generator of the game. Same seed and same actions give same game.
+pub type GameRng = rand_chacha::ChaCha8Rng;
 
 /// Represent current game
 pub struct Game<UI: Context> {
     pub handle: GameHandle<UI>,
+    /// Seed of [`GameHandle::rng`] at game start
+    pub seed: u64,
     pub player_inventory: PlayerInventory,
     pub player_pos: Point,
     pub maps: GameMaps,
+    /// When random ticks are done. Not saved, it's an option of session.
+    pub tick_mode: TickMode,
 }
 
 /// Main game handle with user interface and player information.
@@ -26,6 +46,14 @@ pub struct GameHandle<UI: Context> {
     pub ui: UI,
     pub player: Player,
     pub lore: LoreContents,
+    /// The only random number generator game things should use
+    pub rng: GameRng,
+    /// Dialogue shown in lore with [`LoreContents::CustomEndless`]
+    pub dialogue: Option<ActiveDialogue>,
+    /// Index of selected item in inventory, see [`ItemAction`]
+    pub item_cursor: usize,
+    /// Part of current map shown in main fragment
+    pub camera: Camera,
 }
 
 /// Contents of lore
@@ -37,6 +65,8 @@ pub enum LoreContents {
     Inventory,
     /// Items with page
     Items(u8),
+    /// Information about selected item
+    Inspect,
     /// Endless custom content, set by some game thing.
     CustomEndless,
     /// Custom content, set by some game thing. Erases if ticks less than zero.
@@ -44,6 +74,29 @@ pub enum LoreContents {
 }
 
 impl<UI: Context> GameHandle<UI> {
+    /// Creates handle of new player with random number generator seeded by `seed`
+    pub fn new(ui: UI, seed: u64) -> Self {
+        Self {
+            camera: Camera::new(ui.main_size()),
+            ui,
+            player: Player::new(),
+            lore: LoreContents::Nothing,
+            rng: GameRng::seed_from_u64(seed),
+            dialogue: None,
+            item_cursor: 0,
+        }
+    }
+
+    /// Draw block at map position if it's in view of [`GameHandle::camera`]
+    pub fn draw_block(&mut self, pos: Point, ty: BlockTy) -> Result<(), UI::Error> {
+        let Some(pos) = self.camera.to_screen(pos) else {
+            return Ok(());
+        };
+        let mut m = self.ui.main();
+        m.set_pos(pos)?;
+        m.put_block(ty)
+    }
+
     /// Draws player information in status like HP, XP, etc...
     pub fn draw_player_info(&mut self, limits: &PlayerLimits) -> Result<(), UI::Error> {
         let mut s = self.ui.status();
@@ -51,6 +104,8 @@ impl<UI: Context> GameHandle<UI> {
         write!(s, "狐 ")?;
         s.set_color(Color::Health)?;
         write!(s, "{}/{} HP ", self.player.health, limits.health)?;
+        s.set_color(Color::Hunger)?;
+        write!(s, "{}/{} food ", self.player.hunger, limits.hunger)?;
         s.set_color(Color::XP)?;
         write!(s, "{:3} XP ", self.player.xp)?;
         s.set_color(Color::Gold)?;
@@ -80,7 +135,26 @@ impl<UI: Context> GameHandle<UI> {
     pub fn draw_lore(&mut self, inventory: &PlayerInventory) -> Result<(), UI::Error> {
         self.ui.lore().clear()?;
         match self.lore {
-            LoreContents::Nothing | LoreContents::Custom(0) | LoreContents::CustomEndless => {}
+            LoreContents::Nothing | LoreContents::Custom(0) => {}
+            LoreContents::CustomEndless => {
+                if let Some(dialogue) = &self.dialogue {
+                    let mut l = self.ui.lore();
+                    let node = dialogue.current();
+                    for line in &node.lines {
+                        writeln!(l, "{line}")?;
+                    }
+                    writeln!(l)?;
+                    for (no, choice) in node.choices.iter().enumerate() {
+                        if choice.available(&self.player) {
+                            l.set_color(Color::Normal)?;
+                        } else {
+                            l.set_color(Color::Disabled)?;
+                        }
+                        writeln!(l, "{}. {}", no + 1, choice.text)?;
+                    }
+                    l.set_color(Color::Normal)?;
+                }
+            }
             LoreContents::Custom(v) => self.lore = LoreContents::Custom(v - 1),
             LoreContents::Inventory => {
                 let mut l = self.ui.lore();
@@ -102,19 +176,27 @@ impl<UI: Context> GameHandle<UI> {
             }
             LoreContents::Items(page) => {
                 let mut l = self.ui.lore();
+                let pages = inventory.items.len().div_ceil(ITEMS_PER_PAGE).max(1);
                 l.set_color(Color::GrowingWheatBlock)?;
                 write!(l, "INVENTORY")?;
                 l.set_color(Color::Normal)?;
-                writeln!(l, " page #{}", page as u16 + 1)?;
+                writeln!(l, " page #{}/{pages}", page as u16 + 1)?;
                 writeln!(l)?;
                 let items = inventory
                     .items
                     .iter()
                     .enumerate()
-                    .skip(page as usize * 9)
-                    .take(9);
+                    .skip(page as usize * ITEMS_PER_PAGE)
+                    .take(ITEMS_PER_PAGE);
                 for (no, item) in items {
-                    write!(l, "{}. ", no + 1)?;
+                    if no == self.item_cursor {
+                        l.set_color(Color::MaxValue)?;
+                        write!(l, ">")?;
+                        l.set_color(Color::Normal)?;
+                    } else {
+                        write!(l, " ")?;
+                    }
+                    write!(l, "{}. ", no % ITEMS_PER_PAGE + 1)?;
                     l.set_color(item.item.color())?;
                     write!(l, "{}", item.item.name())?;
                     if item.state.tier != ItemTier::Common {
@@ -127,9 +209,51 @@ impl<UI: Context> GameHandle<UI> {
                     if !meta.is_empty() {
                         write!(l, " ({meta})")?;
                     }
+                    l.set_color(Color::MaxValue)?;
+                    for modifier in item.item.modifiers() {
+                        write!(l, " {}", modifier.scaled(item.state.tier))?;
+                    }
+                    l.set_color(Color::Normal)?;
                     writeln!(l)?;
                 }
             }
+            LoreContents::Inspect => {
+                let Some(item) = inventory.items.get(self.item_cursor) else {
+                    return Ok(());
+                };
+                let mut l = self.ui.lore();
+                l.set_color(Color::GrowingWheatBlock)?;
+                writeln!(l, "ITEM")?;
+                writeln!(l)?;
+                l.set_color(item.item.color())?;
+                write!(l, "{}", item.item.name())?;
+                if item.state.tier != ItemTier::Common {
+                    l.set_color(Color::Normal)?;
+                    write!(l, " ")?;
+                    item.state.tier.suffix(&mut l)?;
+                }
+                l.set_color(Color::Normal)?;
+                writeln!(l)?;
+                let meta = item.item.meta();
+                if !meta.is_empty() {
+                    writeln!(l, "{meta}")?;
+                }
+                let description = item.item.description();
+                if !description.is_empty() {
+                    writeln!(l)?;
+                    writeln!(l, "{description}")?;
+                }
+                let modifiers = item.item.modifiers();
+                if !modifiers.is_empty() {
+                    writeln!(l)?;
+                    writeln!(l, "Bonuses:")?;
+                    l.set_color(Color::MaxValue)?;
+                    for modifier in modifiers {
+                        writeln!(l, " {}", modifier.scaled(item.state.tier))?;
+                    }
+                    l.set_color(Color::Normal)?;
+                }
+            }
         }
 
         Ok(())
@@ -137,17 +261,151 @@ impl<UI: Context> GameHandle<UI> {
 
     /// Toggle inventory in lore
     pub fn toggle_inventory(&mut self) {
+        self.dialogue = None;
         match self.lore {
             LoreContents::Inventory => self.lore = LoreContents::Nothing,
             _ => self.lore = LoreContents::Inventory,
         }
     }
 
-    /// Show next (or first) page of items in lore
+    /// Toggle items page with selected item in lore
     pub fn toggle_items(&mut self) {
-        match &mut self.lore {
-            LoreContents::Items(v) => *v = v.saturating_add(1),
-            _ => self.lore = LoreContents::Items(0),
+        self.dialogue = None;
+        match self.lore {
+            LoreContents::Items(_) | LoreContents::Inspect => self.lore = LoreContents::Nothing,
+            _ => self.lore = LoreContents::Items(self.items_page()),
+        }
+    }
+
+    /// Page of items with selected item
+    fn items_page(&self) -> u8 {
+        (self.item_cursor / ITEMS_PER_PAGE).min(u8::MAX as usize) as u8
+    }
+
+    /// Select numbered (from 1) item on shown page of items
+    pub fn select_item(&mut self, no: u8, inventory: &PlayerInventory) {
+        let LoreContents::Items(page) = self.lore else {
+            return;
+        };
+        let Some(i) = (no as usize).checked_sub(1).filter(|i| *i < ITEMS_PER_PAGE) else {
+            return;
+        };
+        let index = page as usize * ITEMS_PER_PAGE + i;
+        if index < inventory.items.len() {
+            self.item_cursor = index;
+        }
+    }
+
+    /// Do action on selected item or items page. Does nothing if items aren't shown.
+    pub fn item_action(
+        &mut self,
+        action: ItemAction,
+        inventory: &mut PlayerInventory,
+    ) -> Result<(), UI::Error> {
+        if !matches!(self.lore, LoreContents::Items(_) | LoreContents::Inspect) {
+            return Ok(());
+        }
+        let last = inventory.items.len().saturating_sub(1);
+        match action {
+            ItemAction::PrevPage => {
+                self.item_cursor = self.item_cursor.saturating_sub(ITEMS_PER_PAGE)
+            }
+            ItemAction::NextPage => {
+                self.item_cursor = (self.item_cursor + ITEMS_PER_PAGE).min(last)
+            }
+            ItemAction::CursorUp => self.item_cursor = self.item_cursor.saturating_sub(1),
+            ItemAction::CursorDown => self.item_cursor = (self.item_cursor + 1).min(last),
+            ItemAction::Use => self.use_item(self.item_cursor, inventory)?,
+            ItemAction::Drop => {
+                if self.item_cursor < inventory.items.len() {
+                    inventory.items.remove(self.item_cursor);
+                    self.player
+                        .clamp_to(&PlayerLimits::new().with(inventory.items.iter()));
+                }
+            }
+            ItemAction::Inspect => {
+                if self.lore == LoreContents::Items(self.items_page())
+                    && self.item_cursor < inventory.items.len()
+                {
+                    self.lore = LoreContents::Inspect;
+                    return Ok(());
+                }
+            }
+            ItemAction::Sort => inventory.items.sort_by(|a, b| {
+                a.item
+                    .name()
+                    .cmp(&b.item.name())
+                    .then(b.state.tier.cmp(&a.state.tier))
+            }),
+        }
+        self.item_cursor = self
+            .item_cursor
+            .min(inventory.items.len().saturating_sub(1));
+        self.lore = LoreContents::Items(self.items_page());
+        Ok(())
+    }
+
+    /// Use item from inventory by index. Used up item is removed.
+    fn use_item(&mut self, index: usize, inventory: &mut PlayerInventory) -> Result<(), UI::Error> {
+        let Some(ItemData { state, item }) = inventory.items.get_mut(index) else {
+            return Ok(());
+        };
+        let update = ItemUpdateContext {
+            game_handle: self,
+            this: state,
+        };
+        let updates = item.use_item(update)?;
+        if updates.consumed {
+            inventory.items.remove(index);
+        }
+        inventory.items.extend(updates.given);
+        self.player
+            .clamp_to(&PlayerLimits::new().with(inventory.items.iter()));
+        Ok(())
+    }
+
+    /// Show dialogue in lore
+    pub fn start_dialogue(&mut self, dialogue: Dialogue) {
+        self.dialogue = Some(ActiveDialogue::new(dialogue));
+        self.lore = LoreContents::CustomEndless;
+    }
+
+    /// Close dialogue if it's shown
+    pub fn close_dialogue(&mut self) {
+        if self.dialogue.take().is_some() && self.lore == LoreContents::CustomEndless {
+            self.lore = LoreContents::Nothing;
+        }
+    }
+
+    /// Choose numbered (from 1) option of dialogue. Does nothing if option is not available.
+    pub fn choose(&mut self, no: u8, inventory: &mut PlayerInventory) {
+        if self.lore != LoreContents::CustomEndless {
+            return;
+        }
+        let Some(active) = &mut self.dialogue else {
+            return;
+        };
+        let choices = &active.current().choices;
+        let Some(choice) = (no as usize).checked_sub(1).and_then(|i| choices.get(i)) else {
+            return;
+        };
+        if !choice.available(&self.player) {
+            return;
+        }
+
+        let choice = choice.clone();
+        for effect in choice.effects {
+            if let Some(item) = effect.apply(&mut self.player, &mut self.rng) {
+                inventory.items.push(item);
+            }
+        }
+
+        self.player
+            .clamp_to(&PlayerLimits::new().with(inventory.items.iter()));
+
+        match choice.next {
+            Some(node) => active.node = node,
+            None => self.close_dialogue(),
         }
     }
 
@@ -155,7 +413,7 @@ impl<UI: Context> GameHandle<UI> {
     /// It updates only items, use [`Game::do_random_tick`] to update all things.
     pub fn do_random_tick(&mut self, inventory: &mut PlayerInventory) -> Result<(), UI::Error> {
         for ItemData { state, item } in &mut inventory.items {
-            if thread_rng().gen_range(0..100) >= 15 {
+            if self.rng.gen_range(0..100) >= 15 {
                 continue;
             }
             let update = ItemUpdateContext {
@@ -166,9 +424,26 @@ impl<UI: Context> GameHandle<UI> {
         }
         Ok(())
     }
+
+    /// Player gets hungry over time. Starving player loses health, well-fed one heals.
+    pub fn do_hunger_tick(&mut self, limits: &PlayerLimits) {
+        let player = &mut self.player;
+        if self.rng.gen_range(0..100) < HUNGER_CHANCE {
+            player.hunger = player.hunger.saturating_sub(1);
+        }
+        if self.rng.gen_range(0..100) >= HEALTH_CHANCE {
+            return;
+        }
+        if player.hunger == 0 {
+            player.health = player.health.saturating_sub(1);
+        } else if player.hunger * 4 >= limits.hunger * 3 && player.health < limits.health {
+            player.health += 1;
+        }
+    }
 }
 
 /// Action that player can do.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum GameAction {
     /// Move player one block up (-1 by Y)
     MoveUp,
@@ -180,96 +455,316 @@ pub enum GameAction {
     MoveRight,
     /// Interact with block at player position
     Interact,
+    /// Choose numbered (from 1) option in dialogue or item on shown page of items
+    Choose(u8),
+    /// Action on items page
+    Item(ItemAction),
+}
+
+/// Action on items page, see [`LoreContents::Items`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum ItemAction {
+    /// Select item on previous page
+    PrevPage,
+    /// Select item on next page
+    NextPage,
+    /// Select previous item
+    CursorUp,
+    /// Select next item
+    CursorDown,
+    /// Use selected item
+    Use,
+    /// Throw selected item away
+    Drop,
+    /// Show information about selected item
+    Inspect,
+    /// Sort items by name, better tiers first
+    Sort,
 }
 
 impl<UI: Context> Game<UI> {
-    /// Creates new game and init map.
+    /// Creates new game with random seed and init map.
     pub fn new(ui: UI) -> Self {
+        Self::new_with_seed(ui, thread_rng().gen())
+    }
+
+    /// Creates new game and init map. Games with same seed are same.
+    pub fn new_with_seed(ui: UI, seed: u64) -> Self {
         Self {
-            handle: GameHandle {
-                ui,
-                player: Player::new(),
-                lore: LoreContents::Nothing,
-            },
+            handle: GameHandle::new(ui, seed),
+            seed,
             player_pos: Point(0, 0),
             player_inventory: Default::default(),
             maps: GameMaps::init(),
+            tick_mode: TickMode::TurnBased,
+        }
+    }
+
+    /// The only player and the world for one action
+    pub(crate) fn turn(&mut self) -> Turn<'_, UI> {
+        Turn {
+            handle: &mut self.handle,
+            pos: &mut self.player_pos,
+            inventory: &mut self.player_inventory,
+            maps: &mut self.maps,
+            others: &[],
+            tick_mode: self.tick_mode,
         }
     }
 
     /// Calculate player limits
     pub fn player_limits(&self) -> PlayerLimits {
-        PlayerLimits::new().with(self.player_inventory.items.iter().map(|v| &v.state))
+        PlayerLimits::new().with(self.player_inventory.items.iter())
     }
 
-    /// Redraw all blocks on the map
+    /// Redraw all blocks in view. Camera is moved to player first.
     pub fn redraw_all(&mut self) -> Result<(), UI::Error> {
-        let mut m = self.handle.ui.main();
-        for block in self.maps.get_current() {
-            m.set_pos(block.state.pos)?;
-            m.put_block(block.state.ty)?;
-        }
-        m.set_pos(self.player_pos)?;
-        m.put_block(BlockTy::Player)
+        self.turn().redraw_all()
+    }
+
+    /// Clears and redraws all fragments, e.g. after interface was resized
+    pub fn redraw_screen(&mut self) -> Result<(), UI::Error> {
+        self.turn().redraw_screen()
     }
 
     /// Draws player information in status like HP, XP, etc...
     pub fn draw_player_info(&mut self) -> Result<(), UI::Error> {
-        self.handle.draw_player_info(&self.player_limits())
+        self.turn().draw_player_info()
     }
 
     /// Draw (or clear) lore
     pub fn draw_lore(&mut self) -> Result<(), UI::Error> {
-        self.handle.draw_lore(&self.player_inventory)
+        self.turn().draw_lore()
+    }
+
+    /// Do random tick by timer (see [`TickMode::RealTime`]) and redraw player over
+    /// updated blocks.
+    pub fn do_timer_tick(&mut self) -> Result<(), UI::Error> {
+        self.turn().do_timer_tick()
     }
 
     /// Do random tick that updates all things. It automaticly calls on player move, etc...
     pub fn do_random_tick(&mut self) -> Result<(), UI::Error> {
-        self.maps
-            .do_random_tick(&mut self.handle, &mut self.player_inventory)?;
-        self.handle.do_random_tick(&mut self.player_inventory)
+        self.turn().do_random_tick()
     }
 
-    fn update_status_if_needed(&mut self) -> Result<(), UI::Error> {
-        let pos = self.player_pos;
-        if let Some(BlockData {
-            state:
-                BlockState {
-                    collision: CollisionTy::CanUse,
-                    ..
-                },
-            ..
-        }) = self.maps.find_at(pos)
-        {
-            let mut s = self.handle.ui.status();
-            s.set_line(1)?;
+    /// Go to other map by exit. `side` is a direction of player's move, it's used
+    /// when exit has no entry point. If entry is blocked, player appears at the nearest
+    /// free place.
+    ///
+    /// ```
+    /// use ppl_game::{
+    ///     assets::maps::GameMap,
+    ///     game::Game,
+    ///     map::{ExitTrigger, MapExit, Side},
+    ///     ui_impls::headless,
+    /// };
+    ///
+    /// let village = GameMap::Village.init();
+    /// let wall = village.blocks.iter().map(|b| b.state.pos()).find(|p| !village.can_stand(*p));
+    /// let exit = MapExit {
+    ///     trigger: ExitTrigger::Edge(Side::Right),
+    ///     to: GameMap::Village,
+    ///     entry: wall,
+    /// };
+    ///
+    /// let mut game = Game::new_with_seed(headless::Context::new(), 42);
+    /// game.enter_map(exit, Side::Right).unwrap();
+    /// assert_ne!(Some(game.player_pos), wall);
+    /// assert!(village.can_stand(game.player_pos));
+    /// ```
+    pub fn enter_map(&mut self, exit: MapExit, side: Side) -> Result<(), UI::Error> {
+        self.turn().enter_map(exit, side)
+    }
+
+    /// Do [`GameAction`]
+    pub fn do_action(&mut self, act: GameAction) -> Result<(), UI::Error> {
+        self.turn().do_action(act)
+    }
+}
+
+/// One player with the world for one action. [`Game`] has the only player,
+/// [`crate::world::World`] has several of them on the same maps.
+pub(crate) struct Turn<'a, UI: Context> {
+    pub handle: &'a mut GameHandle<UI>,
+    pub pos: &'a mut Point,
+    pub inventory: &'a mut PlayerInventory,
+    /// Maps with player's map as current
+    pub maps: &'a mut GameMaps,
+    /// Maps and positions of other players
+    pub others: &'a [(GameMap, Point)],
+    pub tick_mode: TickMode,
+}
+
+impl<UI: Context> Turn<'_, UI> {
+    fn player_limits(&self) -> PlayerLimits {
+        PlayerLimits::new().with(self.inventory.items.iter())
+    }
+
+    /// Positions of other players on current map
+    fn others_here(&self) -> Vec<Point> {
+        let map = self.maps.current_map;
+        self.others
+            .iter()
+            .filter(|(m, _)| *m == map)
+            .map(|(_, p)| *p)
+            .collect()
+    }
+
+    /// See [`Game::redraw_all`]. Other players are drawn over blocks.
+    pub fn redraw_all(&mut self) -> Result<(), UI::Error> {
+        self.update_camera();
+        let camera = self.handle.camera;
+        let blocks = self.maps.get_current();
+        let mut m = self.handle.ui.main();
+        for pos in camera.screen_points() {
+            if let Some(block) = blocks.get(camera.to_world(pos)) {
+                m.set_pos(pos)?;
+                m.put_block(block.state.ty)?;
+            }
+        }
+        drop(m);
+        for other in self.others_here() {
+            self.handle.draw_block(other, BlockTy::OtherPlayer)?;
+        }
+        self.handle.draw_block(*self.pos, BlockTy::Player)
+    }
+
+    /// Fit camera to main fragment and move it to player. Returns `true` if view changed.
+    fn update_camera(&mut self) -> bool {
+        let size = self.handle.ui.main_size();
+        let camera = &mut self.handle.camera;
+        let resized = camera.size != size;
+        camera.size = size;
+        let moved = camera.follow(*self.pos, self.maps.current().size);
+        resized || moved
+    }
+
+    /// See [`Game::redraw_screen`]
+    pub fn redraw_screen(&mut self) -> Result<(), UI::Error> {
+        self.handle.ui.main().clear()?;
+        self.redraw_all()?;
+        self.handle.ui.status().clear()?;
+        self.draw_player_info()?;
+        self.update_status_if_needed()?;
+        self.draw_lore()
+    }
+
+    pub fn draw_player_info(&mut self) -> Result<(), UI::Error> {
+        self.handle.draw_player_info(&self.player_limits())
+    }
+
+    pub fn draw_lore(&mut self) -> Result<(), UI::Error> {
+        self.handle.draw_lore(self.inventory)
+    }
+
+    /// Do [`crate::replay::Input`] and redraw lore and player info
+    pub fn do_input(&mut self, input: Input) -> Result<(), UI::Error> {
+        match input {
+            Input::Action(act) => self.do_action(act)?,
+            Input::ToggleInventory => self.handle.toggle_inventory(),
+            Input::ToggleItems => self.handle.toggle_items(),
+            Input::Tick => self.do_timer_tick()?,
+        }
+        self.draw_lore()?;
+        self.draw_player_info()
+    }
+
+    /// Do random tick on player's move if game is turn-based
+    fn turn_tick(&mut self) -> Result<(), UI::Error> {
+        match self.tick_mode {
+            TickMode::TurnBased => self.do_random_tick(),
+            TickMode::RealTime(_) => Ok(()),
+        }
+    }
+
+    /// See [`Game::do_timer_tick`]
+    pub fn do_timer_tick(&mut self) -> Result<(), UI::Error> {
+        self.do_random_tick()?;
+        self.handle.draw_block(*self.pos, BlockTy::Player)
+    }
+
+    /// See [`Game::do_random_tick`]
+    pub fn do_random_tick(&mut self) -> Result<(), UI::Error> {
+        self.maps.do_random_tick(self.handle, self.inventory)?;
+        self.do_player_tick()
+    }
+
+    /// Random tick of player's items and hunger, blocks are not updated
+    pub fn do_player_tick(&mut self) -> Result<(), UI::Error> {
+        self.handle.do_random_tick(self.inventory)?;
+        self.handle.do_hunger_tick(&self.player_limits());
+        Ok(())
+    }
+
+    /// Shows in status if player can use something
+    pub fn update_status_if_needed(&mut self) -> Result<(), UI::Error> {
+        let mut s = self.handle.ui.status();
+        s.clear_line(1)?;
+        if self.maps.can_use(*self.pos, &self.handle.player) {
             s.set_color(Color::WaterBlock)?;
-            write!(s, " [can use]")
-        } else {
-            let mut s = self.handle.ui.status();
-            // FIXME: clear one line, not a whole fragment
-            s.clear()
+            write!(s, " [can use]")?;
         }
+        Ok(())
     }
 
-    /// Do [`GameAction`]
+    /// See [`Game::enter_map`]
+    pub fn enter_map(&mut self, exit: MapExit, side: Side) -> Result<(), UI::Error> {
+        self.maps.switch_to(exit.to);
+        let Point(width, height) = self.maps.current().size;
+        let (last_x, last_y) = (width.saturating_sub(1), height.saturating_sub(1));
+        let Point(x, y) = *self.pos;
+        *self.pos = exit.entry.unwrap_or(match side {
+            Side::Up => Point(x.min(last_x), last_y),
+            Side::Down => Point(x.min(last_x), 0),
+            Side::Left => Point(last_x, y.min(last_y)),
+            Side::Right => Point(0, y.min(last_y)),
+        });
+        // entry can be blocked by other player or by block
+        let others = self.others_here();
+        if others.contains(self.pos) || !self.maps.current().can_stand(*self.pos) {
+            // stays there only if the map is full
+            if let Some(pos) = self.maps.free_near(*self.pos, &others) {
+                *self.pos = pos;
+            }
+        }
+        self.handle.ui.main().clear()?;
+        self.redraw_all()?;
+        self.update_status_if_needed()
+    }
+
+    /// See [`Game::do_action`]. Player can't move into other players.
     pub fn do_action(&mut self, act: GameAction) -> Result<(), UI::Error> {
         use GameAction::*;
         match act {
             MoveUp | MoveDown | MoveLeft | MoveRight => 'brk: {
-                let mut pos = self.player_pos;
+                let mut pos = *self.pos;
                 let old_pos = pos;
+                let Point(width, height) = self.maps.current().size;
+                let side = match act {
+                    MoveUp => Side::Up,
+                    MoveDown => Side::Down,
+                    MoveLeft => Side::Left,
+                    _ => Side::Right,
+                };
                 match act {
                     MoveUp if pos.1 != 0 => pos.1 -= 1,
-                    MoveDown if pos.1 != MAX_POINT.1 => pos.1 += 1,
+                    MoveDown if pos.1 + 1 < height => pos.1 += 1,
                     MoveLeft if pos.0 != 0 => pos.0 -= 1,
-                    MoveRight if pos.0 != MAX_POINT.0 => pos.0 += 1,
-                    _ => break 'brk,
+                    MoveRight if pos.0 + 1 < width => pos.0 += 1,
+                    _ => {
+                        // leaving the map through side
+                        if let Some(exit) = self.maps.find_exit(ExitTrigger::Edge(side)) {
+                            self.turn_tick()?;
+                            self.enter_map(exit, side)?;
+                        }
+                        break 'brk;
+                    }
                 };
                 if let Some(BlockData {
                     state:
                         BlockState {
-                            collision: CollisionTy::Collision,
+                            collision: CollisionTy::Collision | CollisionTy::UseNearby,
                             ..
                         },
                     ..
@@ -277,28 +772,38 @@ impl<UI: Context> Game<UI> {
                 {
                     break 'brk;
                 }
-                self.player_pos = pos;
-                self.do_random_tick()?;
-                let mut m = self.handle.ui.main();
-                m.set_pos(old_pos)?;
-                m.put_block(
-                    self.maps
-                        .find_at(old_pos)
-                        .map_or(BlockTy::Air, |f| f.state.ty),
-                )?;
-                m.set_pos(pos)?;
-                m.put_block(BlockTy::Player)?;
-                drop(m);
+                if self.others_here().contains(&pos) {
+                    break 'brk;
+                }
+                *self.pos = pos;
+                self.handle.close_dialogue();
+                self.turn_tick()?;
+                if let Some(exit) = self.maps.find_exit(ExitTrigger::At(pos)) {
+                    self.enter_map(exit, side)?;
+                    break 'brk;
+                }
+                if self.update_camera() {
+                    self.handle.ui.main().clear()?;
+                    self.redraw_all()?;
+                } else {
+                    let old = self.maps.find_at(old_pos);
+                    let old_ty = old.map_or(BlockTy::Air, |f| f.state.ty);
+                    self.handle.draw_block(old_pos, old_ty)?;
+                    self.handle.draw_block(pos, BlockTy::Player)?;
+                }
                 self.update_status_if_needed()?;
             }
             Interact => {
-                self.maps.interact_at(
-                    self.player_pos,
-                    &mut self.handle,
-                    &mut self.player_inventory,
-                )?;
+                if let Some(pos) = self.maps.interact_target(*self.pos, &self.handle.player) {
+                    self.maps.interact_at(pos, self.handle, self.inventory)?;
+                }
                 self.update_status_if_needed()?;
             }
+            Choose(no) => match self.handle.lore {
+                LoreContents::Items(_) => self.handle.select_item(no, self.inventory),
+                _ => self.handle.choose(no, self.inventory),
+            },
+            Item(action) => self.handle.item_action(action, self.inventory)?,
         }
         Ok(())
     }
@@ -1,8 +1,13 @@
 #[allow(clippy::new_without_default)]
 pub mod assets;
+pub mod camera;
+pub mod clock;
 pub mod game;
 pub mod map;
 pub mod player;
+pub mod replay;
+pub mod save;
 pub mod things;
 pub mod ui;
 pub mod ui_impls;
+pub mod world;
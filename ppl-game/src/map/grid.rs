@@ -0,0 +1,217 @@
+//! Dense grid of blocks
+//!
+//! Map keeps at most one block per position. Grid grows to fit blocks inserted out of it,
+//! lookup by position doesn't depend on number of blocks.
+//!
+//! # Example
+//! ```
+//! use ppl_game::{
+//!     assets::blocks::generic::Generic,
+//!     map::grid::BlockGrid,
+//!     things::{BlockData, CollisionTy},
+//!     ui::{BlockTy, Point},
+//! };
+//!
+//! let block = |x, y, ty| BlockData::new(Point(x, y), CollisionTy::Collision, ty, Generic.into());
+//! let mut grid = BlockGrid::new();
+//! assert!(grid.insert(block(3, 1, BlockTy::Wall)).is_ok());
+//! assert!(grid.insert(block(0, 0, BlockTy::Wall)).is_ok());
+//! let old = grid.insert(block(3, 1, BlockTy::Wheat)).ok().flatten();
+//!
+//! assert_eq!(old.map(|b| b.state.ty), Some(BlockTy::Wall));
+//! assert_eq!(grid.len(), 2);
+//! assert_eq!(grid.size(), Point(4, 2));
+//! assert_eq!(grid.get(Point(3, 1)).map(|b| b.state.ty), Some(BlockTy::Wheat));
+//! assert!(grid.get(Point(1, 1)).is_none());
+//! assert!(grid.insert(block(u16::MAX, 0, BlockTy::Wall)).is_err());
+//!
+//! // blocks are iterated by lines
+//! let positions: Vec<_> = grid.iter().map(|b| b.state.pos()).collect();
+//! assert_eq!(positions, [Point(0, 0), Point(3, 1)]);
+//!
+//! // loaded block can't be out of `MAX_SIZE`
+//! let saved = "[(state:(pos:(60000,1),collision:Collision,ty:Wall),block:Generic(()))]";
+//! assert!(ron::from_str::<BlockGrid>(saved).is_err());
+//! ```
+
+use std::fmt;
+
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{things::BlockData, ui::Point};
+
+/// Largest map (cols; lines) that can be loaded. Grid has a cell for every position, so
+/// size from a save must not allocate whatever it asks.
+pub const MAX_SIZE: Point = Point(1024, 1024);
+
+/// Blocks of map indexed by position. Saved as a list of blocks.
+#[derive(Default)]
+pub struct BlockGrid {
+    /// Cols and lines of grid
+    size: Point,
+    cells: Vec<Option<BlockData>>,
+    len: usize,
+}
+
+impl BlockGrid {
+    /// Creates empty grid
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates empty grid with space for blocks inside `size`
+    pub fn with_size(size: Point) -> Self {
+        let mut cells = Vec::new();
+        cells.resize_with(size.0 as usize * size.1 as usize, || None);
+        Self {
+            size,
+            cells,
+            len: 0,
+        }
+    }
+
+    /// Size of grid (cols; lines), all blocks are inside it
+    pub fn size(&self) -> Point {
+        self.size
+    }
+
+    /// Number of blocks
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Are there no blocks
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Block at position
+    pub fn get(&self, pos: Point) -> Option<&BlockData> {
+        self.index(pos).and_then(|i| self.cells[i].as_ref())
+    }
+    /// Block at position
+    pub fn get_mut(&mut self, pos: Point) -> Option<&mut BlockData> {
+        self.index(pos).and_then(|i| self.cells[i].as_mut())
+    }
+
+    /// Puts block at it's position, grows grid if needed. Returns block that was there.
+    /// Block with coordinate `u16::MAX` doesn't fit in grid and is given back as error.
+    pub fn insert(&mut self, block: BlockData) -> Result<Option<BlockData>, BlockData> {
+        let pos = block.state.pos();
+        if !self.grow_to(pos) {
+            return Err(block);
+        }
+        let i = self.index(pos).expect("grid is grown to position");
+        let old = self.cells[i].replace(block);
+        if old.is_none() {
+            self.len += 1;
+        }
+        Ok(old)
+    }
+
+    /// Puts loaded block. It must be inside `bound` (and [`MAX_SIZE`]) and the only one at
+    /// it's position.
+    pub(super) fn insert_loaded(&mut self, block: BlockData, bound: Point) -> Result<(), String> {
+        let Point(x, y) = block.state.pos();
+        let Point(cols, lines) = bound;
+        let out = || format!("block at ({x}; {y}) is out of {cols}x{lines}");
+        if x >= cols.min(MAX_SIZE.0) || y >= lines.min(MAX_SIZE.1) {
+            return Err(out());
+        }
+        match self.insert(block) {
+            Ok(None) => Ok(()),
+            Ok(Some(_)) => Err(format!("two blocks at ({x}; {y})")),
+            Err(_) => Err(out()),
+        }
+    }
+
+    /// Takes block from position
+    pub fn remove(&mut self, pos: Point) -> Option<BlockData> {
+        let old = self.index(pos).and_then(|i| self.cells[i].take());
+        if old.is_some() {
+            self.len -= 1;
+        }
+        old
+    }
+
+    /// Blocks line by line
+    pub fn iter(&self) -> impl Iterator<Item = &BlockData> {
+        self.cells.iter().flatten()
+    }
+    /// Blocks line by line
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut BlockData> {
+        self.cells.iter_mut().flatten()
+    }
+
+    fn index(&self, Point(x, y): Point) -> Option<usize> {
+        (x < self.size.0 && y < self.size.1)
+            .then_some(y as usize * self.size.0 as usize + x as usize)
+    }
+
+    /// Makes grid big enough for `pos`. Adding lines is cheap, adding cols moves all blocks.
+    /// Returns `false` if `pos` can't fit in grid.
+    fn grow_to(&mut self, Point(x, y): Point) -> bool {
+        let (Some(x_end), Some(y_end)) = (x.checked_add(1), y.checked_add(1)) else {
+            return false;
+        };
+        let width = self.size.0.max(x_end);
+        let height = self.size.1.max(y_end);
+        if width != self.size.0 {
+            let mut grid = Self::with_size(Point(width, height));
+            for block in self.cells.drain(..).flatten() {
+                let i = grid.index(block.state.pos()).expect("new grid is larger");
+                grid.cells[i] = Some(block);
+                grid.len += 1;
+            }
+            *self = grid;
+        } else if height != self.size.1 {
+            self.cells
+                .resize_with(width as usize * height as usize, || None);
+            self.size.1 = height;
+        }
+        true
+    }
+}
+
+impl<'a> IntoIterator for &'a BlockGrid {
+    type Item = &'a BlockData;
+    type IntoIter = std::iter::Flatten<std::slice::Iter<'a, Option<BlockData>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.iter().flatten()
+    }
+}
+
+impl Serialize for BlockGrid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for BlockGrid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct GridVisitor;
+
+        impl<'de> Visitor<'de> for GridVisitor {
+            type Value = BlockGrid;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a list of blocks")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<BlockGrid, A::Error> {
+                let mut grid = BlockGrid::new();
+                while let Some(block) = seq.next_element::<BlockData>()? {
+                    grid.insert_loaded(block, MAX_SIZE)
+                        .map_err(de::Error::custom)?;
+                }
+                Ok(grid)
+            }
+        }
+
+        deserializer.deserialize_seq(GridVisitor)
+    }
+}
@@ -0,0 +1,337 @@
+pub mod grid;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::{blocks::BlockBehavior, maps::GameMap},
+    game::GameHandle,
+    player::{Player, PlayerInventory},
+    things::{BlockData, BlockState, BlockUpdateContext, CollisionTy},
+    ui::{self, Point},
+};
+
+use self::grid::{BlockGrid, MAX_SIZE};
+
+/// Side of the map
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Side {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// What makes player leave the map
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum ExitTrigger {
+    /// Player steps onto block (door)
+    At(Point),
+    /// Player leaves the map through side
+    Edge(Side),
+}
+
+/// Exit to other map
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct MapExit {
+    pub trigger: ExitTrigger,
+    /// Map where player goes
+    pub to: GameMap,
+    /// Position of player on new map. If `None` player appears on the opposite side
+    /// (only for [`ExitTrigger::Edge`]).
+    pub entry: Option<Point>,
+}
+
+/// Blocks and exits of one map
+///
+/// Loaded map can't have blocks out of its size or be larger than
+/// [`MAX_SIZE`](grid::MAX_SIZE):
+/// ```
+/// use ppl_game::map::MapData;
+///
+/// let block = "(state:(pos:(3,1),collision:Collision,ty:Wall),block:Generic(()))";
+/// assert!(ron::from_str::<MapData>(&format!("(size:(4,2),blocks:[{block}],exits:[])")).is_ok());
+/// assert!(ron::from_str::<MapData>(&format!("(size:(3,2),blocks:[{block}],exits:[])")).is_err());
+/// let huge = "(size:(65535,65535),blocks:[],exits:[])";
+/// assert!(ron::from_str::<MapData>(huge).is_err());
+/// ```
+#[derive(Default, Serialize, Deserialize)]
+#[serde(try_from = "SavedMapData")]
+pub struct MapData {
+    /// Size of map (cols; lines). Player can't go out of it, all blocks are inside.
+    pub size: Point,
+    pub blocks: BlockGrid,
+    pub exits: Vec<MapExit>,
+}
+
+/// [`MapData`] as it's saved. Blocks are checked before grid is grown for them.
+#[derive(Deserialize)]
+struct SavedMapData {
+    size: Point,
+    blocks: Vec<BlockData>,
+    exits: Vec<MapExit>,
+}
+
+impl MapData {
+    /// Can player stand at `pos`: it's inside map and block there (if any) lets in
+    pub fn can_stand(&self, pos: Point) -> bool {
+        let inside = pos.0 < self.size.0 && pos.1 < self.size.1;
+        inside
+            && self.blocks.get(pos).is_none_or(|b| {
+                !matches!(
+                    b.state.collision,
+                    CollisionTy::Collision | CollisionTy::UseNearby
+                )
+            })
+    }
+}
+
+impl TryFrom<SavedMapData> for MapData {
+    type Error = String;
+
+    fn try_from(saved: SavedMapData) -> Result<Self, String> {
+        let Point(cols, lines) = saved.size;
+        if cols > MAX_SIZE.0 || lines > MAX_SIZE.1 {
+            let Point(max_cols, max_lines) = MAX_SIZE;
+            return Err(format!(
+                "map {cols}x{lines} is larger than {max_cols}x{max_lines}"
+            ));
+        }
+        let mut blocks = BlockGrid::new();
+        for block in saved.blocks {
+            blocks.insert_loaded(block, saved.size)?;
+        }
+        Ok(Self {
+            size: saved.size,
+            blocks,
+            exits: saved.exits,
+        })
+    }
+}
+
+/// Game maps. Some maps may be not init (until player visits them).
+/// Default map is [`GameMap::Farm`]
+#[derive(Serialize, Deserialize)]
+pub struct GameMaps {
+    pub current_map: GameMap,
+    pub farm: MapData,
+    pub village: Option<MapData>,
+    pub forest: Option<MapData>,
+}
+
+impl GameMaps {
+    /// Initialize default game map
+    pub fn init() -> Self {
+        Self {
+            current_map: GameMap::Farm,
+            farm: GameMap::Farm.init(),
+            village: None,
+            forest: None,
+        }
+    }
+
+    /// Switch current map. Initialize it if it is not yet.
+    pub fn switch_to(&mut self, map: GameMap) {
+        match map {
+            GameMap::Farm => {}
+            GameMap::Village => _ = self.village.get_or_insert_with(|| map.init()),
+            GameMap::Forest => _ = self.forest.get_or_insert_with(|| map.init()),
+        }
+        self.current_map = map;
+    }
+
+    /// Get current map data
+    pub fn current(&self) -> &MapData {
+        let map = match self.current_map {
+            GameMap::Farm => Some(&self.farm),
+            GameMap::Village => self.village.as_ref(),
+            GameMap::Forest => self.forest.as_ref(),
+        };
+        map.expect("current map is initialized")
+    }
+    /// Get current map data
+    pub fn current_mut(&mut self) -> &mut MapData {
+        let map = match self.current_map {
+            GameMap::Farm => Some(&mut self.farm),
+            GameMap::Village => self.village.as_mut(),
+            GameMap::Forest => self.forest.as_mut(),
+        };
+        map.expect("current map is initialized")
+    }
+
+    /// Get current map (blocks on it)
+    pub fn get_current(&self) -> &BlockGrid {
+        &self.current().blocks
+    }
+    /// Get current map (blocks on it)
+    pub fn get_current_mut(&mut self) -> &mut BlockGrid {
+        &mut self.current_mut().blocks
+    }
+
+    /// Find exit of current map by trigger
+    pub fn find_exit(&self, trigger: ExitTrigger) -> Option<MapExit> {
+        self.current()
+            .exits
+            .iter()
+            .find(|exit| exit.trigger == trigger)
+            .copied()
+    }
+
+    /// Find block by position
+    pub fn find_at(&self, pos: Point) -> Option<&BlockData> {
+        self.get_current().get(pos)
+    }
+    /// Find block that `player` at `pos` interacts with. It's block at `pos` that is usable
+    /// or handles interaction itself (e.g. wheat when player has water), then block near
+    /// `pos` that can be used from adjacent block and then any block at `pos`.
+    ///
+    /// ```
+    /// use ppl_game::{
+    ///     assets::blocks::{water::Water, wheat::Wheat},
+    ///     game::{Game, GameAction},
+    ///     things::{BlockData, CollisionTy},
+    ///     ui::{BlockTy, Point},
+    ///     ui_impls::headless,
+    /// };
+    ///
+    /// let mut game = Game::new_with_seed(headless::Context::new(), 42);
+    /// let blocks = game.maps.get_current_mut();
+    /// let wheat = BlockData::new(Point(0, 0), CollisionTy::CanUse, BlockTy::Wheat, Wheat::new().into());
+    /// let water = BlockData::new(Point(1, 0), CollisionTy::UseNearby, BlockTy::Water, Water.into());
+    /// assert!(blocks.insert(wheat).is_ok() && blocks.insert(water).is_ok());
+    /// game.player_pos = Point(0, 0);
+    /// game.handle.player.water = 0;
+    ///
+    /// // harvest, wheat underfoot grows again
+    /// game.do_action(GameAction::Interact).unwrap();
+    /// let ty = game.maps.find_at(Point(0, 0)).unwrap().state.ty;
+    /// assert_eq!(ty, BlockTy::GrowingWheat);
+    ///
+    /// // bucket is empty, so it's filled from water nearby
+    /// assert_eq!(game.maps.interact_target(Point(0, 0), &game.handle.player), Some(Point(1, 0)));
+    /// assert!(game.maps.can_use(Point(0, 0), &game.handle.player));
+    /// game.do_action(GameAction::Interact).unwrap();
+    /// assert_eq!(game.handle.player.water, 1);
+    ///
+    /// // now wheat underfoot is watered
+    /// game.do_action(GameAction::Interact).unwrap();
+    /// assert_eq!(game.handle.player.water, 0);
+    /// ```
+    pub fn interact_target(&self, pos: Point, player: &Player) -> Option<Point> {
+        let here = self.find_at(pos);
+        if here.is_some_and(|b| {
+            b.state.collision == CollisionTy::CanUse || b.block.interacts_from_inside(player)
+        }) {
+            return Some(pos);
+        }
+        pos.neighbours()
+            .find(|&p| {
+                self.find_at(p)
+                    .is_some_and(|b| b.state.collision == CollisionTy::UseNearby)
+            })
+            .or(here.map(|_| pos))
+    }
+
+    /// Place on current map nearest to `pos` where player can stand and that is not
+    /// `taken`. `None` if there is no such place.
+    pub fn free_near(&self, pos: Point, taken: &[Point]) -> Option<Point> {
+        let Point(width, height) = self.current().size;
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| Point(x, y)))
+            .filter(|p| self.current().can_stand(*p) && !taken.contains(p))
+            .min_by_key(|p| p.0.abs_diff(pos.0) + p.1.abs_diff(pos.1))
+    }
+
+    /// Can `player` at `pos` use something
+    pub fn can_use(&self, pos: Point, player: &Player) -> bool {
+        self.interact_target(pos, player)
+            .and_then(|p| self.find_at(p))
+            .is_some_and(|b| {
+                matches!(
+                    b.state.collision,
+                    CollisionTy::CanUse | CollisionTy::UseNearby
+                )
+            })
+    }
+
+    /// Find block by position
+    pub fn find_at_mut(&mut self, pos: Point) -> Option<&mut BlockData> {
+        self.get_current_mut().get_mut(pos)
+    }
+
+    /// Do random tick that updates all blocks. Call it on interval or on player move, etc...
+    /// It updates only blocks, use [`crate::game::Game::do_random_tick`] to update all things.
+    pub fn do_random_tick<UI: ui::Context>(
+        &mut self,
+        game_handle: &mut GameHandle<UI>,
+        player_inventory: &mut PlayerInventory,
+    ) -> Result<(), UI::Error> {
+        let mut other_updates = Vec::new();
+        let mut updated = Vec::new();
+        let blocks = self.get_current_mut();
+        let Point(width, height) = blocks.size();
+        let positions = (0..height).flat_map(|y| (0..width).map(move |x| Point(x, y)));
+        for pos in positions {
+            if blocks.get(pos).is_none() || game_handle.rng.gen_range(0..100) >= 15 {
+                continue;
+            }
+            let neighbours = neighbours(blocks, pos);
+            let BlockData { state, block } = blocks.get_mut(pos).expect("block is checked");
+            let update = BlockUpdateContext {
+                game_handle,
+                this: state,
+                player_inventory,
+                neighbours,
+            };
+            let updates = block.update(update)?;
+            updated.push(pos);
+            other_updates.push(updates.other);
+        }
+        for pos in updated {
+            if let Some(data) = blocks.get(pos) {
+                game_handle.draw_block(pos, data.state.ty)?;
+            }
+        }
+        for (p, s) in other_updates.into_iter().flatten() {
+            if let Some(data) = self.find_at_mut(p) {
+                data.state.merge_with(s);
+                game_handle.draw_block(p, data.state.ty)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Interact with block by it's position
+    pub fn interact_at<UI: ui::Context>(
+        &mut self,
+        pos: Point,
+        game_handle: &mut GameHandle<UI>,
+        player_inventory: &mut PlayerInventory,
+    ) -> Result<(), UI::Error> {
+        let blocks = self.get_current_mut();
+        let neighbours = neighbours(blocks, pos);
+        if let Some(BlockData { state, block }) = blocks.get_mut(pos) {
+            let update = BlockUpdateContext {
+                game_handle,
+                this: state,
+                player_inventory,
+                neighbours,
+            };
+            let updates = block.interact(update)?;
+            updates.other.into_iter().for_each(|(p, s)| {
+                if let Some(data) = self.find_at_mut(p) {
+                    data.state.merge_with(s);
+                }
+            })
+        }
+        Ok(())
+    }
+}
+
+/// States of blocks adjacent to `pos`
+fn neighbours(blocks: &BlockGrid, pos: Point) -> Vec<BlockState> {
+    pos.neighbours()
+        .filter_map(|p| blocks.get(p))
+        .map(|b| b.state.clone())
+        .collect()
+}
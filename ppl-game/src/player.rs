@@ -1,10 +1,20 @@
 //! Player information
 
-use crate::things::{ItemData, ItemState};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::items::ItemBehavior,
+    things::{ItemData, ItemTier},
+};
 
 /// Represents player
+#[derive(Serialize, Deserialize)]
 pub struct Player {
     pub health: u32,
+    /// How full player is. Drops over time, player loses health when it's zero.
+    pub hunger: u32,
     pub xp: u32,
     pub gold: u32,
 
@@ -12,9 +22,77 @@ pub struct Player {
     pub water: u32,
 }
 
+/// Player limit that can be changed by [`Modifier`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum LimitStat {
+    Health,
+    Wheat,
+    Water,
+}
+
+impl LimitStat {
+    pub const ALL: [Self; 3] = [Self::Health, Self::Wheat, Self::Water];
+}
+
+/// How [`Modifier`] changes limit
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum ModifierKind {
+    /// Adds value to limit
+    Flat(u32),
+    /// Adds percent of limit. Applied after all flat modifiers.
+    Percent(u32),
+}
+
+/// Change of player limit given by item
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Modifier {
+    pub stat: LimitStat,
+    pub kind: ModifierKind,
+}
+
+impl Modifier {
+    pub const fn flat(stat: LimitStat, value: u32) -> Self {
+        Self {
+            stat,
+            kind: ModifierKind::Flat(value),
+        }
+    }
+    pub const fn percent(stat: LimitStat, value: u32) -> Self {
+        Self {
+            stat,
+            kind: ModifierKind::Percent(value),
+        }
+    }
+
+    /// Modifier of item with tier `tier`
+    pub fn scaled(self, tier: ItemTier) -> Self {
+        let scale = |v: u32| v.saturating_mul(tier.bonus_percent()) / 100;
+        let kind = match self.kind {
+            ModifierKind::Flat(v) => ModifierKind::Flat(scale(v)),
+            ModifierKind::Percent(v) => ModifierKind::Percent(scale(v)),
+        };
+        Self { kind, ..self }
+    }
+}
+
+impl fmt::Display for Modifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stat = match self.stat {
+            LimitStat::Health => "HP",
+            LimitStat::Wheat => "wheat",
+            LimitStat::Water => "water",
+        };
+        match self.kind {
+            ModifierKind::Flat(v) => write!(f, "+{v} {stat}"),
+            ModifierKind::Percent(v) => write!(f, "+{v}% {stat}"),
+        }
+    }
+}
+
 /// Represents player's limits
 pub struct PlayerLimits {
     pub health: u32,
+    pub hunger: u32,
 
     pub wheat: u32,
     pub water: u32,
@@ -25,14 +103,70 @@ impl PlayerLimits {
     pub fn new() -> Self {
         Self {
             health: 10,
+            hunger: 20,
             wheat: 500,
             water: 4,
         }
     }
-    /// Apply item effects on limits
-    pub fn with<'a>(self, _iter: impl Iterator<Item = &'a ItemState>) -> Self {
+
+    /// Apply item modifiers on limits. Flat modifiers are summed first, then the sum of
+    /// percent modifiers is applied.
+    ///
+    /// # Example
+    /// ```
+    /// use ppl_game::{
+    ///     assets::items::ItemKind, game::GameRng, player::PlayerLimits, things::ItemTier,
+    /// };
+    /// use rand::SeedableRng;
+    ///
+    /// let mut sack = ItemKind::Sack.create(&mut GameRng::seed_from_u64(0));
+    /// sack.state.tier = ItemTier::Common;
+    ///
+    /// let limits = PlayerLimits::new().with([&sack].into_iter());
+    /// assert_eq!(limits.wheat, 750);
+    /// assert_eq!(limits.water, PlayerLimits::new().water);
+    /// ```
+    pub fn with<'a>(mut self, iter: impl Iterator<Item = &'a ItemData>) -> Self {
+        let mut flat = Self::ZERO;
+        let mut percent = Self::ZERO;
+        for item in iter {
+            for modifier in item.item.modifiers() {
+                let Modifier { stat, kind } = modifier.scaled(item.state.tier);
+                match kind {
+                    ModifierKind::Flat(v) => {
+                        let sum = flat.get_mut(stat);
+                        *sum = sum.saturating_add(v);
+                    }
+                    ModifierKind::Percent(v) => {
+                        let sum = percent.get_mut(stat);
+                        *sum = sum.saturating_add(v);
+                    }
+                }
+            }
+        }
+
+        for stat in LimitStat::ALL {
+            let base = self.get_mut(stat).saturating_add(*flat.get_mut(stat));
+            let bonus = base.saturating_mul(*percent.get_mut(stat)) / 100;
+            *self.get_mut(stat) = base.saturating_add(bonus);
+        }
         self
     }
+
+    const ZERO: Self = Self {
+        health: 0,
+        hunger: 0,
+        wheat: 0,
+        water: 0,
+    };
+
+    fn get_mut(&mut self, stat: LimitStat) -> &mut u32 {
+        match stat {
+            LimitStat::Health => &mut self.health,
+            LimitStat::Wheat => &mut self.wheat,
+            LimitStat::Water => &mut self.water,
+        }
+    }
 }
 impl Default for PlayerLimits {
     fn default() -> Self {
@@ -41,10 +175,19 @@ impl Default for PlayerLimits {
 }
 
 impl Player {
+    /// Reduce stats that are above limits
+    pub fn clamp_to(&mut self, limits: &PlayerLimits) {
+        self.health = self.health.min(limits.health);
+        self.hunger = self.hunger.min(limits.hunger);
+        self.wheat = self.wheat.min(limits.wheat);
+        self.water = self.water.min(limits.water);
+    }
+
     /// Creates new player.
     pub fn new() -> Self {
         Self {
             health: 10,
+            hunger: 20,
             xp: 1,
             gold: 0,
             wheat: 0,
@@ -59,7 +202,7 @@ impl Default for Player {
 }
 
 /// Represents player's inventory
-#[derive(Default)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct PlayerInventory {
     pub items: Vec<ItemData>,
 }
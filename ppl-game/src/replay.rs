@@ -0,0 +1,192 @@
+//! # Recording and replaying
+//!
+//! Game is deterministic: same seed and same [`Input`]s give the same state. So a play
+//! session can be recorded as a seed and a stream of timestamped inputs, and replayed later
+//! to reproduce bugs. Recording also contains a hash of final state (see
+//! [`Game::state_hash`]), so replay can tell if it has diverged.
+//!
+//! # Example
+//! ```
+//! use ppl_game::{
+//!     game::{Game, GameAction},
+//!     replay::{Input, Recorder, Verdict},
+//!     ui_impls::headless,
+//! };
+//!
+//! let mut game = Game::new_with_seed(headless::Context::new(), 7);
+//! let mut recorder = Recorder::new(game.seed, game.tick_mode);
+//! for input in [
+//!     Input::Action(GameAction::MoveRight),
+//!     Input::ToggleInventory,
+//!     Input::Action(GameAction::MoveDown),
+//! ] {
+//!     recorder.record(input);
+//!     game.do_input(input).unwrap();
+//! }
+//! let recording = recorder.finish(&game).unwrap();
+//!
+//! let mut replayed = recording.start(headless::Context::new());
+//! let verdict = recording.replay(&mut replayed).unwrap();
+//! assert_eq!(verdict, Verdict::Matched);
+//! assert_eq!(replayed.player_pos, game.player_pos);
+//! ```
+
+use std::{fs, path::Path, time::Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    clock::TickMode,
+    game::{Game, GameAction},
+    save::SaveError,
+    ui,
+};
+
+/// Version of recording format. Recordings with other version can't be loaded.
+pub const RECORDING_VERSION: u32 = 3;
+
+/// Anything that changes game state or interface
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Input {
+    /// See [`Game::do_action`]
+    Action(GameAction),
+    /// See [`crate::game::GameHandle::toggle_inventory`]
+    ToggleInventory,
+    /// See [`crate::game::GameHandle::toggle_items`]
+    ToggleItems,
+    /// Timer tick in real-time mode, see [`Game::do_timer_tick`]
+    Tick,
+}
+
+/// Recorded input with time from start of recording
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Event {
+    /// Milliseconds from start of recording
+    pub at_ms: u64,
+    pub input: Input,
+}
+
+/// Recorded play session
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Recording {
+    pub version: u32,
+    /// Seed of the game, see [`Game::new_with_seed`]
+    pub seed: u64,
+    pub tick_mode: TickMode,
+    pub events: Vec<Event>,
+    /// Hash of game state after all events
+    pub final_hash: Option<u64>,
+}
+
+/// Result of replay
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Verdict {
+    /// State after replay is the same as recorded
+    Matched,
+    /// State after replay differs, game or recording has changed
+    Diverged { expected: u64, actual: u64 },
+    /// Recording has no final hash (it was not finished)
+    Unknown,
+    /// State after replay can't be hashed
+    Error(String),
+}
+
+impl<UI: ui::Context> Game<UI> {
+    /// Do [`Input`] and redraw lore and player info. Doesn't apply changes.
+    pub fn do_input(&mut self, input: Input) -> Result<(), UI::Error> {
+        self.turn().do_input(input)
+    }
+
+    /// Hash of whole saved game state (see [`crate::save`]). It's stable between runs
+    /// and platforms.
+    pub fn state_hash(&self) -> Result<u64, SaveError> {
+        // FNV-1a
+        let hash = self
+            .save_to_string()?
+            .bytes()
+            .fold(0xcbf29ce484222325, |hash, b| {
+                (hash ^ b as u64).wrapping_mul(0x100000001b3)
+            });
+        Ok(hash)
+    }
+}
+
+impl Recording {
+    /// Creates new game for replay
+    pub fn start<UI: ui::Context>(&self, ui: UI) -> Game<UI> {
+        let mut game = Game::new_with_seed(ui, self.seed);
+        game.tick_mode = self.tick_mode;
+        game
+    }
+
+    /// Do all events at once and compare final state.
+    /// `game` should be created by [`Recording::start`].
+    pub fn replay<UI: ui::Context>(&self, game: &mut Game<UI>) -> Result<Verdict, UI::Error> {
+        for event in &self.events {
+            game.do_input(event.input)?;
+        }
+        Ok(self.verify(game))
+    }
+
+    /// Compare state of game with final state of recording
+    pub fn verify<UI: ui::Context>(&self, game: &Game<UI>) -> Verdict {
+        let Some(expected) = self.final_hash else {
+            return Verdict::Unknown;
+        };
+        match game.state_hash() {
+            Ok(actual) if actual == expected => Verdict::Matched,
+            Ok(actual) => Verdict::Diverged { expected, actual },
+            Err(e) => Verdict::Error(e.to_string()),
+        }
+    }
+
+    /// Saves recording to file
+    pub fn save_to(&self, path: impl AsRef<Path>) -> Result<(), SaveError> {
+        let s = ron::ser::to_string_pretty(self, Default::default())?;
+        fs::write(path, s)?;
+        Ok(())
+    }
+
+    /// Loads recording from file
+    pub fn load_from(path: impl AsRef<Path>) -> Result<Self, SaveError> {
+        let recording: Self = ron::from_str(&fs::read_to_string(path)?)?;
+        if recording.version != RECORDING_VERSION {
+            return Err(SaveError::Version(recording.version));
+        }
+        Ok(recording)
+    }
+}
+
+/// Records [`Input`]s with their time
+pub struct Recorder {
+    recording: Recording,
+    started: Instant,
+}
+
+impl Recorder {
+    /// Starts recording of the game with `seed`. Start it with new game.
+    pub fn new(seed: u64, tick_mode: TickMode) -> Self {
+        Self {
+            recording: Recording {
+                version: RECORDING_VERSION,
+                seed,
+                tick_mode,
+                events: Vec::new(),
+                final_hash: None,
+            },
+            started: Instant::now(),
+        }
+    }
+
+    /// Record input. Call it for every input passed to the game.
+    pub fn record(&mut self, input: Input) {
+        let at_ms = self.started.elapsed().as_millis() as u64;
+        self.recording.events.push(Event { at_ms, input });
+    }
+
+    /// Finish recording with hash of final game state.
+    pub fn finish<UI: ui::Context>(mut self, game: &Game<UI>) -> Result<Recording, SaveError> {
+        self.recording.final_hash = Some(game.state_hash()?);
+        Ok(self.recording)
+    }
+}
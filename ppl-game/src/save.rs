@@ -0,0 +1,150 @@
+//! # Saving and loading
+//!
+//! Whole game state (player, inventory, all maps with local block states, random number
+//! generator) is saved as a [RON](https://docs.rs/ron) document. User interface, lore and
+//! dialogue are not saved.
+//!
+//! Use [`Game::save_to`] and [`Game::load_from`] to work with files.
+//!
+//! # Example
+//! ```
+//! use ppl_game::{game::{Game, GameAction}, ui_impls::headless};
+//!
+//! let mut game = Game::new_with_seed(headless::Context::new(), 1);
+//! game.do_action(GameAction::MoveRight).unwrap();
+//! let save = game.save_to_string().unwrap();
+//!
+//! let mut loaded = Game::new(headless::Context::new());
+//! loaded.load_from_str(&save).unwrap();
+//! assert_eq!(loaded.player_pos, game.player_pos);
+//! assert_eq!(loaded.save_to_string().unwrap(), save);
+//! ```
+
+use std::{fmt, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{Game, GameRng, LoreContents},
+    map::GameMaps,
+    player::{Player, PlayerInventory},
+    ui::{self, Point},
+};
+
+/// Version of save format. Saves with other version can't be loaded.
+pub const SAVE_VERSION: u32 = 6;
+
+/// Error while saving or loading game
+#[derive(Debug)]
+pub enum SaveError {
+    /// Can't read or write save file
+    Io(io::Error),
+    /// Can't serialize game state
+    Serialize(ron::Error),
+    /// Save file is corrupted
+    Deserialize(ron::error::SpannedError),
+    /// Save file made by other version of game
+    Version(u32),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "save i/o error: {e}"),
+            Self::Serialize(e) => write!(f, "can't serialize game: {e}"),
+            Self::Deserialize(e) => write!(f, "corrupted save: {e}"),
+            Self::Version(v) => write!(f, "unsupported save version {v} (expected {SAVE_VERSION})"),
+        }
+    }
+}
+impl std::error::Error for SaveError {}
+
+impl From<io::Error> for SaveError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+impl From<ron::Error> for SaveError {
+    fn from(value: ron::Error) -> Self {
+        Self::Serialize(value)
+    }
+}
+impl From<ron::error::SpannedError> for SaveError {
+    fn from(value: ron::error::SpannedError) -> Self {
+        Self::Deserialize(value)
+    }
+}
+
+/// Borrowed game state, used for saving.
+#[derive(Serialize)]
+struct SaveRef<'a> {
+    version: u32,
+    seed: u64,
+    rng: &'a GameRng,
+    player: &'a Player,
+    player_inventory: &'a PlayerInventory,
+    player_pos: Point,
+    maps: &'a GameMaps,
+}
+
+/// Owned game state, used for loading.
+#[derive(Deserialize)]
+struct SaveData {
+    version: u32,
+    seed: u64,
+    rng: GameRng,
+    player: Player,
+    player_inventory: PlayerInventory,
+    player_pos: Point,
+    maps: GameMaps,
+}
+
+impl<UI: ui::Context> Game<UI> {
+    /// Serializes game state into string.
+    pub fn save_to_string(&self) -> Result<String, SaveError> {
+        let save = SaveRef {
+            version: SAVE_VERSION,
+            seed: self.seed,
+            rng: &self.handle.rng,
+            player: &self.handle.player,
+            player_inventory: &self.player_inventory,
+            player_pos: self.player_pos,
+            maps: &self.maps,
+        };
+        Ok(ron::ser::to_string_pretty(&save, Default::default())?)
+    }
+
+    /// Replaces game state with state from string made by [`Game::save_to_string`].
+    /// Lore is reset, caller should redraw interface.
+    pub fn load_from_str(&mut self, s: &str) -> Result<(), SaveError> {
+        let save: SaveData = ron::from_str(s)?;
+        if save.version != SAVE_VERSION {
+            return Err(SaveError::Version(save.version));
+        }
+
+        self.seed = save.seed;
+        self.handle.rng = save.rng;
+        self.handle.player = save.player;
+        self.handle.lore = LoreContents::Nothing;
+        self.handle.dialogue = None;
+        self.handle.item_cursor = 0;
+        self.player_inventory = save.player_inventory;
+        self.player_pos = save.player_pos;
+        self.maps = save.maps;
+        Ok(())
+    }
+
+    /// Saves game state to file. File is replaced only if the whole state was written.
+    pub fn save_to(&self, path: impl AsRef<Path>) -> Result<(), SaveError> {
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, self.save_to_string()?)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    /// Loads game state from file. See [`Game::load_from_str`].
+    pub fn load_from(&mut self, path: impl AsRef<Path>) -> Result<(), SaveError> {
+        self.load_from_str(&fs::read_to_string(path)?)
+    }
+}
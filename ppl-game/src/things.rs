@@ -11,11 +11,12 @@
 
 use std::ops::RangeInclusive;
 
-use rand::{thread_rng, Rng};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     assets::{blocks::Block, items::Item},
-    game::GameHandle,
+    game::{GameHandle, GameRng},
     player::{PlayerInventory, PlayerLimits},
     ui::{self, BlockTy, Color, Point},
 };
@@ -33,11 +34,23 @@ pub struct BlockUpdateContext<'a, UI: ui::Context> {
     pub game_handle: &'a mut GameHandle<UI>,
     pub player_inventory: &'a mut PlayerInventory,
     pub this: &'a mut BlockState,
+    /// States of adjacent blocks, taken before update
+    pub neighbours: Vec<BlockState>,
 }
 
 impl<'a, UI: ui::Context> BlockUpdateContext<'a, UI> {
+    /// Is there adjacent block with type `ty`
+    pub fn has_neighbour(&self, ty: BlockTy) -> bool {
+        self.neighbours.iter().any(|s| s.ty == ty)
+    }
+
     pub fn player_limits(&self) -> PlayerLimits {
-        PlayerLimits::new().with(self.player_inventory.items.iter().map(|v| &v.state))
+        PlayerLimits::new().with(self.player_inventory.items.iter())
+    }
+
+    /// Game random number generator. Don't use other generators in game things.
+    pub fn rng(&mut self) -> &mut GameRng {
+        &mut self.game_handle.rng
     }
 }
 
@@ -45,10 +58,15 @@ impl<'a, UI: ui::Context, T> UpdateContext<'a, UI, T> {
     pub fn new(game_handle: &'a mut GameHandle<UI>, this: &'a mut T) -> Self {
         Self { game_handle, this }
     }
+
+    /// Game random number generator. Don't use other generators in game things.
+    pub fn rng(&mut self) -> &mut GameRng {
+        &mut self.game_handle.rng
+    }
 }
 
 /// Tier of item.
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default, Serialize, Deserialize)]
 pub enum ItemTier {
     /// Common tier, without any "выебона"
     #[default]
@@ -63,10 +81,11 @@ pub enum ItemTier {
     LevelPlus,
 }
 /// Item state
-#[derive(Default)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct ItemState {
     pub tier: ItemTier,
 }
+#[derive(Serialize, Deserialize)]
 pub struct ItemData {
     pub state: ItemState,
     pub item: Item,
@@ -93,13 +112,25 @@ impl ItemTier {
         }
     }
 
-    pub fn rand(range: RangeInclusive<Self>) -> Self {
+    /// Random tier from range. Higher tiers are rarer.
+    pub fn rand(range: RangeInclusive<Self>, rng: &mut impl Rng) -> Self {
         let min = range.start().rnd_minmax().0;
         let max = range.end().rnd_minmax().1;
-        let id = thread_rng().gen_range(min..=max);
+        let id = rng.gen_range(min..=max);
         Self::rnd_get(id)
     }
 
+    /// Percent of item bonuses (see [`crate::player::Modifier`]) given by tier
+    pub fn bonus_percent(self) -> u32 {
+        match self {
+            Self::Common => 100,
+            Self::LevelC => 125,
+            Self::LevelB => 150,
+            Self::LevelA => 200,
+            Self::LevelPlus => 250,
+        }
+    }
+
     /// Puts suffix to [`TextFragment`]
     pub fn suffix<T>(self, l: &mut T) -> Result<(), T::Error>
     where
@@ -127,31 +158,38 @@ impl ItemTier {
 }
 
 /// Block state
+#[derive(Clone, Serialize, Deserialize)]
 pub struct BlockState {
-    pub pos: Point,
+    /// Cell of block in map, can't be changed: map keeps one block per cell
+    pos: Point,
     pub collision: CollisionTy,
     pub ty: BlockTy,
 }
-/// Partial block state. Can be merged into [`BlockState`] using [`BlockState::merge_with`]
+/// Partial block state. Can be merged into [`BlockState`] using [`BlockState::merge_with`].
+/// Position can't be changed: it's the cell of block in map.
 #[derive(Default)]
 pub struct PartialBlockState {
-    pub pos: Option<Point>,
     pub collision: Option<CollisionTy>,
     pub ty: Option<BlockTy>,
 }
 
 impl BlockState {
+    pub fn new(pos: Point, collision: CollisionTy, ty: BlockTy) -> Self {
+        Self { pos, collision, ty }
+    }
+
+    /// Position of block in map
+    pub fn pos(&self) -> Point {
+        self.pos
+    }
+
     /// Merges with partial state.
     ///
     /// # Example
     /// ```
     /// use ppl_game::{things::{BlockState, PartialBlockState, CollisionTy}, ui::{BlockTy, Point}};
     ///
-    /// let mut block = BlockState {
-    ///     ty: BlockTy::GrowingWheat,
-    ///     collision: CollisionTy::NoCollision,
-    ///     pos: Point(1, 1),
-    /// };
+    /// let mut block = BlockState::new(Point(1, 1), CollisionTy::NoCollision, BlockTy::GrowingWheat);
     /// let partial = PartialBlockState {
     ///     ty: Some(BlockTy::Wheat),
     ///     ..Default::default()
@@ -159,12 +197,9 @@ impl BlockState {
     ///
     /// block.merge_with(partial);
     /// assert_eq!(block.ty, BlockTy::Wheat);
-    /// assert_eq!(block.pos, Point(1, 1));
+    /// assert_eq!(block.pos(), Point(1, 1));
     /// ```
     pub fn merge_with(&mut self, partial: PartialBlockState) {
-        if let Some(pos) = partial.pos {
-            self.pos = pos
-        }
         if let Some(collision) = partial.collision {
             self.collision = collision
         }
@@ -175,6 +210,7 @@ impl BlockState {
 }
 
 /// Full block data in map
+#[derive(Serialize, Deserialize)]
 pub struct BlockData {
     /// Block generic state
     pub state: BlockState,
@@ -182,11 +218,14 @@ pub struct BlockData {
     pub block: Block,
 }
 /// Type of collision
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum CollisionTy {
     /// Player can move into block
     NoCollision,
     /// Player can move into block and interact with it
     CanUse,
+    /// Player can't move into block, but can interact with it from adjacent block
+    UseNearby,
     /// Player can't move into block
     Collision,
 }
@@ -195,7 +234,7 @@ impl BlockData {
     /// Creates new block
     pub fn new(pos: Point, collision: CollisionTy, ty: BlockTy, block: Block) -> Self {
         Self {
-            state: BlockState { pos, collision, ty },
+            state: BlockState::new(pos, collision, ty),
             block,
         }
     }
@@ -47,18 +47,31 @@
 
 use std::fmt;
 
-use crate::things::BlockState;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::registry::{Registry, ThingName},
+    things::BlockState,
+};
 
 /// Represents an block.
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
 pub enum BlockTy {
+    #[default]
     Air,
 
     Player,
+    /// Other player in shared world, see [`crate::world`]. Its glyph differs from the
+    /// player's one, so they are told apart without colors.
+    OtherPlayer,
     NPCFarmer,
 
     Wheat,
     GrowingWheat,
+    /// Growing wheat without water
+    DryWheat,
+
+    Tree,
 
     Wall,
     WallDoor,
@@ -66,12 +79,47 @@ pub enum BlockTy {
     Water,
     BridgeV,
     BridgeH,
+
+    /// Block declared in [registry](crate::assets::registry)
+    Custom(ThingName),
+}
+
+impl BlockTy {
+    /// Char that represents block in text interfaces
+    pub fn glyph(self) -> char {
+        use BlockTy::*;
+        match self {
+            Air => ' ',
+
+            Player => '@',
+            OtherPlayer => 'P',
+            NPCFarmer => '&',
+
+            Wheat => '#',
+            GrowingWheat => '+',
+            DryWheat => ',',
+
+            Tree => '^',
+
+            Wall => '#',
+            WallDoor => '-',
+
+            Water => '%',
+            BridgeV => '|',
+            BridgeH => '-',
+
+            Custom(name) => Registry::get().block(name).map_or('?', |b| b.glyph),
+        }
+    }
 }
 
 /// Represents text color.
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
 pub enum Color {
+    #[default]
     Normal,
+    /// Something that can't be used now
+    Disabled,
     RareItem,
     SpecialItem,
 
@@ -80,6 +128,7 @@ pub enum Color {
     WaterBlock,
 
     Health,
+    Hunger,
     XP,
     Gold,
     Wheat,
@@ -88,16 +137,31 @@ pub enum Color {
 }
 
 /// Represents a point(X; Y).
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
 pub struct Point(pub u16, pub u16);
 
+impl Point {
+    /// Adjacent points: up, down, left and right. Points out of `u16` are skipped.
+    pub fn neighbours(self) -> impl Iterator<Item = Point> {
+        let Point(x, y) = self;
+        [
+            y.checked_sub(1).map(|y| Point(x, y)),
+            y.checked_add(1).map(|y| Point(x, y)),
+            x.checked_sub(1).map(|x| Point(x, y)),
+            x.checked_add(1).map(|x| Point(x, y)),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
 /// Full user interface context
 pub trait Context {
     /// 2 lines fragment with status.
     type Status<'a>: TextFragment<Error = Self::Error>
     where
         Self: 'a;
-    /// Main fragment, 12 lines and 30 cols.
+    /// Main fragment, view of map. See [`Context::main_size`].
     type Main<'a>: BlockFragment<Error = Self::Error>
     where
         Self: 'a;
@@ -114,6 +178,8 @@ pub trait Context {
     fn main(&mut self) -> Self::Main<'_>;
     /// Obtain lore handle.
     fn lore(&mut self) -> Self::Lore<'_>;
+    /// Size of main fragment (cols; lines)
+    fn main_size(&self) -> Point;
 
     /// Apply all changes
     fn apply(&mut self) -> Result<(), Self::Error>;
@@ -131,6 +197,8 @@ pub trait Fragment {
 
     /// Clear all fragment and jump to (0; 0)
     fn clear(&mut self) -> Result<(), Self::Error>;
+    /// Clear one line and jump to its start
+    fn clear_line(&mut self, line: u16) -> Result<(), Self::Error>;
 }
 /// Interface [`Fragment`] that made of blocks
 pub trait BlockFragment: Fragment {
@@ -156,7 +224,7 @@ pub trait BlockFragmentExt: BlockFragment {
         &mut self,
         block_state: &BlockState,
     ) -> Result<(), <Self as Fragment>::Error> {
-        self.set_pos(block_state.pos)?;
+        self.set_pos(block_state.pos())?;
         self.put_block(block_state.ty)
     }
 }
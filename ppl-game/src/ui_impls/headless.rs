@@ -0,0 +1,304 @@
+//! Headless (in-memory) User Interface
+//!
+//! Fragments are grids of chars (or blocks) with colors that can be read back.
+//! It's useful for tests and for running game without terminal. Fragments are placed by
+//! [`Layout`] like TUI ones, so headless context of terminal size sees what player sees.
+//!
+//! # Example
+//! ```
+//! use ppl_game::{game::{Game, GameAction}, ui::{BlockTy, Point}, ui_impls::headless};
+//!
+//! let mut game = Game::new_with_seed(headless::Context::new(), 42);
+//! game.redraw_all().unwrap();
+//! game.do_action(GameAction::MoveRight).unwrap();
+//! game.draw_player_info().unwrap();
+//!
+//! assert_eq!(game.handle.ui.block_at(Point(1, 0)), BlockTy::Player);
+//! assert_eq!(game.handle.ui.block_at(Point(0, 0)), BlockTy::Air);
+//! assert!(game.handle.ui.status_line(0).contains("10/10 HP"));
+//! println!("{}", game.handle.ui.render_to_string());
+//!
+//! // fragments of 100x30 terminal
+//! let ui = headless::Context::with_size(Point(100, 30));
+//! assert_eq!(ui.main.size(), Point(66, 27));
+//! assert_eq!(ui.lore.size(), Point(32, 27));
+//! ```
+
+use std::convert::Infallible;
+
+use crate::ui::{self, BlockTy, Color, Point};
+
+use super::layout::{Layout, DEFAULT_SIZE};
+
+/// One char of text fragment
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TextCell {
+    pub ch: char,
+    pub color: Color,
+}
+impl Default for TextCell {
+    fn default() -> Self {
+        Self {
+            ch: ' ',
+            color: Color::Normal,
+        }
+    }
+}
+
+/// Grid of cells with fixed size
+#[derive(Clone, Debug)]
+pub struct Grid<T> {
+    size: Point,
+    cells: Vec<T>,
+}
+
+impl<T: Copy + Default> Grid<T> {
+    /// Creates new grid filled with default cells
+    pub fn new(size: Point) -> Self {
+        Self {
+            size,
+            cells: vec![T::default(); size.0 as usize * size.1 as usize],
+        }
+    }
+
+    /// Size of grid (cols; lines)
+    pub fn size(&self) -> Point {
+        self.size
+    }
+
+    /// Get cell. Returns `None` if `pos` is out of grid
+    pub fn get(&self, pos: Point) -> Option<T> {
+        self.index(pos).map(|i| self.cells[i])
+    }
+    /// Set cell. Does nothing if `pos` is out of grid
+    pub fn set(&mut self, pos: Point, cell: T) {
+        if let Some(i) = self.index(pos) {
+            self.cells[i] = cell;
+        }
+    }
+
+    /// Fill grid with default cells
+    pub fn clear(&mut self) {
+        self.cells.fill(T::default());
+    }
+    /// Fill one line with default cells
+    pub fn clear_line(&mut self, y: u16) {
+        for x in 0..self.size.0 {
+            self.set(Point(x, y), T::default());
+        }
+    }
+
+    /// Get one line of grid
+    pub fn line(&self, y: u16) -> &[T] {
+        let width = self.size.0 as usize;
+        let start = (y as usize * width).min(self.cells.len());
+        &self.cells[start..(start + width).min(self.cells.len())]
+    }
+
+    fn index(&self, Point(x, y): Point) -> Option<usize> {
+        (x < self.size.0 && y < self.size.1)
+            .then_some(y as usize * self.size.0 as usize + x as usize)
+    }
+}
+
+/// Main struct of user interface. Implements [`crate::ui::Context`] trait
+#[derive(Clone, Debug)]
+pub struct Context {
+    pub status: Grid<TextCell>,
+    pub main: Grid<BlockTy>,
+    pub lore: Grid<TextCell>,
+    applied: usize,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Context {
+    /// Creates new context with empty fragments of terminal with [`DEFAULT_SIZE`]
+    pub fn new() -> Self {
+        Self::with_size(DEFAULT_SIZE)
+    }
+
+    /// Creates new context with empty fragments of terminal with `size`. Fragments of too
+    /// small terminal are empty, like in TUI.
+    pub fn with_size(size: Point) -> Self {
+        let layout = Layout::new(size);
+        let size = |rect: fn(&Layout) -> Point| layout.as_ref().map_or(Point(0, 0), rect);
+        Self {
+            status: Grid::new(size(|l| l.status.size)),
+            main: Grid::new(size(|l| l.main.size)),
+            lore: Grid::new(size(|l| l.lore.size)),
+            applied: 0,
+        }
+    }
+
+    /// How many times [`ui::Context::apply`] was called
+    pub fn applied(&self) -> usize {
+        self.applied
+    }
+
+    /// Block in main fragment. Returns [`BlockTy::Air`] if `pos` is out of fragment.
+    pub fn block_at(&self, pos: Point) -> BlockTy {
+        self.main.get(pos).unwrap_or(BlockTy::Air)
+    }
+
+    /// Text of status line without trailing spaces
+    pub fn status_line(&self, y: u16) -> String {
+        text_line(&self.status, y)
+    }
+    /// Text of lore line without trailing spaces
+    pub fn lore_line(&self, y: u16) -> String {
+        text_line(&self.lore, y)
+    }
+
+    /// Render all fragments like `tui` does, but without colors.
+    pub fn render_to_string(&self) -> String {
+        let mut s = String::new();
+        for y in 0..self.status.size().1 {
+            s += &self.status_line(y);
+            s.push('\n');
+        }
+
+        let width = self.main.size().0 as usize;
+        s.extend("-[ MAP ]".chars().chain(std::iter::repeat('-')).take(width));
+        s.push('+');
+        let rest = self.lore.size().0 as usize + 1;
+        s.extend("-[ LORE ]".chars().chain(std::iter::repeat('-')).take(rest));
+        s.push('\n');
+
+        for y in 0..self.main.size().1.max(self.lore.size().1) {
+            s.extend(self.main.line(y).iter().map(|b| b.glyph()));
+            s += "| ";
+            s += &self.lore_line(y);
+            let trimmed = s.trim_end_matches(' ').len();
+            s.truncate(trimmed);
+            s.push('\n');
+        }
+        s
+    }
+}
+
+fn text_line(grid: &Grid<TextCell>, y: u16) -> String {
+    let line: String = grid.line(y).iter().map(|c| c.ch).collect();
+    line.trim_end().to_owned()
+}
+
+impl ui::Context for Context {
+    type Error = Infallible;
+    type Status<'a> = TextFragment<'a>;
+    type Main<'a> = BlockFragment<'a>;
+    type Lore<'a> = TextFragment<'a>;
+
+    fn status(&mut self) -> Self::Status<'_> {
+        TextFragment::new(&mut self.status)
+    }
+    fn main(&mut self) -> Self::Main<'_> {
+        BlockFragment {
+            grid: &mut self.main,
+            pos: Point(0, 0),
+        }
+    }
+    fn lore(&mut self) -> Self::Lore<'_> {
+        TextFragment::new(&mut self.lore)
+    }
+    fn main_size(&self) -> Point {
+        self.main.size()
+    }
+
+    fn apply(&mut self) -> Result<(), Self::Error> {
+        self.applied += 1;
+        Ok(())
+    }
+}
+
+/// Text fragment, implements [`ui::TextFragment`]
+pub struct TextFragment<'a> {
+    grid: &'a mut Grid<TextCell>,
+    pos: Point,
+    color: Color,
+}
+
+impl<'a> TextFragment<'a> {
+    fn new(grid: &'a mut Grid<TextCell>) -> Self {
+        Self {
+            grid,
+            pos: Point(0, 0),
+            color: Color::Normal,
+        }
+    }
+}
+
+impl ui::Fragment for TextFragment<'_> {
+    type Error = Infallible;
+
+    fn set_pos(&mut self, pos: Point) -> Result<(), Self::Error> {
+        self.pos = pos;
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<(), Self::Error> {
+        self.grid.clear();
+        self.pos = Point(0, 0);
+        Ok(())
+    }
+    fn clear_line(&mut self, line: u16) -> Result<(), Self::Error> {
+        self.grid.clear_line(line);
+        self.pos = Point(0, line);
+        Ok(())
+    }
+}
+impl ui::TextFragment for TextFragment<'_> {
+    fn set_color(&mut self, color: Color) -> Result<(), Self::Error> {
+        self.color = color;
+        Ok(())
+    }
+
+    fn put_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        for ch in s.chars() {
+            if ch == '\n' {
+                self.pos = Point(0, self.pos.1.saturating_add(1));
+                continue;
+            }
+            let color = self.color;
+            self.grid.set(self.pos, TextCell { ch, color });
+            self.pos.0 = self.pos.0.saturating_add(1);
+        }
+        Ok(())
+    }
+}
+
+/// Block fragment, implements [`ui::BlockFragment`]
+pub struct BlockFragment<'a> {
+    grid: &'a mut Grid<BlockTy>,
+    pos: Point,
+}
+
+impl ui::Fragment for BlockFragment<'_> {
+    type Error = Infallible;
+
+    fn set_pos(&mut self, pos: Point) -> Result<(), Self::Error> {
+        self.pos = pos;
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<(), Self::Error> {
+        self.grid.clear();
+        self.pos = Point(0, 0);
+        Ok(())
+    }
+    fn clear_line(&mut self, line: u16) -> Result<(), Self::Error> {
+        self.grid.clear_line(line);
+        self.pos = Point(0, line);
+        Ok(())
+    }
+}
+impl ui::BlockFragment for BlockFragment<'_> {
+    fn put_block(&mut self, block: BlockTy) -> Result<(), Self::Error> {
+        self.grid.set(self.pos, block);
+        self.pos.0 = self.pos.0.saturating_add(1);
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+//! Placement of fragments on terminal
+//!
+//! ```text
+//! STATUS (2 lines)
+//! -[ MAP ]----------+-[ LORE ]---
+//! MAIN              | LORE
+//! ```
+//!
+//! Lore takes about a third of width (but not more than [`LORE_WIDTH`]), main takes the
+//! rest. Terminal smaller than [`MIN_SIZE`] has no layout. Headless interface uses the
+//! same layout, so it has fragments of the same sizes as terminal.
+//!
+//! # Example
+//! ```
+//! use ppl_game::{ui::Point, ui_impls::layout::{Layout, Rect}};
+//!
+//! let layout = Layout::new(Point(80, 24)).unwrap();
+//! assert_eq!(layout.main, Rect { pos: Point(0, 3), size: Point(52, 21) });
+//! assert_eq!(layout.lore, Rect { pos: Point(54, 3), size: Point(26, 21) });
+//!
+//! assert!(Layout::new(Point(40, 24)).is_none());
+//! ```
+
+use crate::ui::Point;
+
+/// Size of terminal when it can't be asked
+pub const DEFAULT_SIZE: Point = Point(80, 24);
+/// Smallest terminal (cols; lines) game can be shown on
+pub const MIN_SIZE: Point = Point(60, 15);
+/// Maximum width of lore
+pub const LORE_WIDTH: u16 = 32;
+/// Minimum width of lore
+pub const MIN_LORE_WIDTH: u16 = 20;
+/// Lines of status
+pub const STATUS_LINES: u16 = 2;
+
+/// Rectangle on terminal, `pos` is top left corner (from 0)
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Rect {
+    pub pos: Point,
+    pub size: Point,
+}
+
+impl Rect {
+    /// Is point (relative to rectangle) inside it
+    pub fn contains(&self, Point(x, y): Point) -> bool {
+        x < self.size.0 && y < self.size.1
+    }
+}
+
+/// Rectangles of fragments
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Layout {
+    /// Size of terminal
+    pub size: Point,
+    pub status: Rect,
+    pub main: Rect,
+    pub lore: Rect,
+}
+
+impl Layout {
+    /// Layout for terminal of `size`. Returns `None` if it's smaller than [`MIN_SIZE`].
+    pub fn new(size: Point) -> Option<Self> {
+        let Point(cols, lines) = size;
+        if cols < MIN_SIZE.0 || lines < MIN_SIZE.1 {
+            return None;
+        }
+        // status, then line of border
+        let top = STATUS_LINES + 1;
+        // two columns between main and lore: border and space
+        let lore_width = ((cols - 2) / 3).clamp(MIN_LORE_WIDTH, LORE_WIDTH);
+        let main_width = cols - 2 - lore_width;
+        Some(Self {
+            size,
+            status: Rect {
+                pos: Point(0, 0),
+                size: Point(cols, STATUS_LINES),
+            },
+            main: Rect {
+                pos: Point(0, top),
+                size: Point(main_width, lines - top),
+            },
+            lore: Rect {
+                pos: Point(main_width + 2, top),
+                size: Point(lore_width, lines - top),
+            },
+        })
+    }
+}
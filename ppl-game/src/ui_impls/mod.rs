@@ -1,6 +1,11 @@
 //! # User Interface Implementations
 //!
 //! 1. [`tui`] terminal user interface. Implemented for unix(-like) os. Requires feature `tui`
+//! 2. [`headless`] in-memory interface without terminal, useful for tests.
+//!
+//! Both place fragments by [`layout`].
 
+pub mod headless;
+pub mod layout;
 #[cfg(feature = "tui")]
 pub mod tui;
@@ -0,0 +1,646 @@
+//! Terminal input
+//!
+//! [`InputDecoder`] turns bytes from terminal (UTF-8 text, control chars and ANSI/xterm
+//! escape sequences) into [`InputEvent`]s. [`InputReader`] reads them from stdin, pty or
+//! socket.
+//!
+//! # Example
+//! ```
+//! use ppl_game::{
+//!     ui::Point,
+//!     ui_impls::tui::input::{InputDecoder, InputEvent, Key, KeyEvent, Modifiers},
+//! };
+//!
+//! let mut decoder = InputDecoder::new();
+//! decoder.feed("w\x1b[A\x1b[1;5Cж\x1b[<0;3;4M".as_bytes());
+//!
+//! let key = |key| Some(InputEvent::Key(KeyEvent::new(key)));
+//! assert_eq!(decoder.next(), key(Key::Char('w')));
+//! assert_eq!(decoder.next(), key(Key::Up));
+//! assert_eq!(
+//!     decoder.next(),
+//!     Some(InputEvent::Key(KeyEvent {
+//!         key: Key::Right,
+//!         mods: Modifiers { ctrl: true, ..Default::default() },
+//!     }))
+//! );
+//! assert_eq!(decoder.next(), key(Key::Char('ж')));
+//! assert!(matches!(decoder.next(), Some(InputEvent::Mouse(m)) if m.pos == Point(2, 3)));
+//! assert_eq!(decoder.next(), None);
+//!
+//! // lone escape is a key only when nothing follows it
+//! decoder.feed(b"\x1b");
+//! assert_eq!(decoder.next(), None);
+//! assert_eq!(decoder.flush(), key(Key::Esc));
+//! decoder.feed(b"\x1b[");
+//! assert_eq!(decoder.next(), None);
+//! decoder.feed(b"A");
+//! assert_eq!(decoder.next(), key(Key::Up));
+//!
+//! // key names, used in configs
+//! let ctrl_s: KeyEvent = "ctrl+s".parse().unwrap();
+//! decoder.feed(b"\x13");
+//! assert_eq!(decoder.next(), Some(InputEvent::Key(ctrl_s)));
+//! assert_eq!(ctrl_s.to_string(), "ctrl+s");
+//! ```
+
+use std::{
+    fmt,
+    io::{self, Read},
+    os::fd::AsRawFd,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::ui::Point;
+
+/// Escape sequences longer than this are dropped
+const MAX_SEQUENCE: usize = 32;
+/// Time to wait for the rest of unfinished input before it's flushed, see
+/// [`InputReader::poll_event`]
+const FLUSH_DELAY: Duration = Duration::from_millis(50);
+
+/// Key without modifiers
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Key {
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    /// Function key, from 1
+    F(u8),
+    Enter,
+    Tab,
+    Backspace,
+    Esc,
+}
+
+/// Pressed modifiers
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub alt: bool,
+    pub ctrl: bool,
+}
+
+impl Modifiers {
+    /// Modifiers from xterm parameter (`1 + bits`, where shift is 1, alt is 2 and ctrl is 4)
+    fn from_param(param: u16) -> Self {
+        let bits = param.saturating_sub(1);
+        Self {
+            shift: bits & 1 != 0,
+            alt: bits & 2 != 0,
+            ctrl: bits & 4 != 0,
+        }
+    }
+}
+
+/// Key with modifiers. Shifted chars are reported as other chars (`A`, not `shift+a`).
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct KeyEvent {
+    pub key: Key,
+    pub mods: Modifiers,
+}
+
+impl KeyEvent {
+    /// Key without modifiers
+    pub fn new(key: Key) -> Self {
+        Self {
+            key,
+            mods: Modifiers::default(),
+        }
+    }
+
+    fn with(key: Key, mods: Modifiers) -> Self {
+        Self { key, mods }
+    }
+}
+
+impl fmt::Display for KeyEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.mods.ctrl {
+            write!(f, "ctrl+")?;
+        }
+        if self.mods.alt {
+            write!(f, "alt+")?;
+        }
+        if self.mods.shift {
+            write!(f, "shift+")?;
+        }
+        match self.key {
+            Key::Char(' ') => write!(f, "space"),
+            Key::Char(ch) => write!(f, "{ch}"),
+            Key::Up => write!(f, "up"),
+            Key::Down => write!(f, "down"),
+            Key::Left => write!(f, "left"),
+            Key::Right => write!(f, "right"),
+            Key::Home => write!(f, "home"),
+            Key::End => write!(f, "end"),
+            Key::PageUp => write!(f, "pageup"),
+            Key::PageDown => write!(f, "pagedown"),
+            Key::Insert => write!(f, "insert"),
+            Key::Delete => write!(f, "delete"),
+            Key::F(n) => write!(f, "f{n}"),
+            Key::Enter => write!(f, "enter"),
+            Key::Tab => write!(f, "tab"),
+            Key::Backspace => write!(f, "backspace"),
+            Key::Esc => write!(f, "esc"),
+        }
+    }
+}
+
+/// Error of parsing [`KeyEvent`] from name
+#[derive(Debug)]
+pub struct UnknownKey(pub String);
+
+impl fmt::Display for UnknownKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown key `{}`", self.0)
+    }
+}
+impl std::error::Error for UnknownKey {}
+
+impl FromStr for KeyEvent {
+    type Err = UnknownKey;
+
+    /// Parses names like `w`, `S`, `ctrl+s`, `alt+shift+up`, `f1` or `space`.
+    /// Inverse of [`fmt::Display`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || UnknownKey(s.to_owned());
+        let mut mods = Modifiers::default();
+        let mut rest = s;
+        while let Some((m, tail)) = rest.split_once('+').filter(|(_, tail)| !tail.is_empty()) {
+            match m {
+                "ctrl" => mods.ctrl = true,
+                "alt" => mods.alt = true,
+                "shift" => mods.shift = true,
+                _ => return Err(err()),
+            }
+            rest = tail;
+        }
+        let key = match rest {
+            "space" => Key::Char(' '),
+            "up" => Key::Up,
+            "down" => Key::Down,
+            "left" => Key::Left,
+            "right" => Key::Right,
+            "home" => Key::Home,
+            "end" => Key::End,
+            "pageup" => Key::PageUp,
+            "pagedown" => Key::PageDown,
+            "insert" => Key::Insert,
+            "delete" => Key::Delete,
+            "enter" => Key::Enter,
+            "tab" => Key::Tab,
+            "backspace" => Key::Backspace,
+            "esc" => Key::Esc,
+            _ => match rest.strip_prefix('f').map(str::parse) {
+                Some(Ok(n @ 1..=24)) => Key::F(n),
+                _ => {
+                    let mut chars = rest.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(ch), None) if !ch.is_control() => Key::Char(ch),
+                        _ => return Err(err()),
+                    }
+                }
+            },
+        };
+        Ok(Self { key, mods })
+    }
+}
+
+/// Mouse button
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+/// What mouse did
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MouseKind {
+    Press(MouseButton),
+    /// Button released. Old terminals don't report which one.
+    Release(Option<MouseButton>),
+    /// Mouse moved with pressed button
+    Drag(MouseButton),
+    /// Mouse moved without pressed buttons
+    Move,
+    ScrollUp,
+    ScrollDown,
+}
+
+/// Mouse event. Reported only if terminal mouse tracking is enabled.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MouseEvent {
+    pub kind: MouseKind,
+    /// Position on screen, from (0; 0)
+    pub pos: Point,
+    pub mods: Modifiers,
+}
+
+/// Terminal input event
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InputEvent {
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+    /// Terminal is resized to (cols; lines). Reported as answer on window size query
+    /// (`CSI 18 t`) or with in-band resize notifications.
+    Resize(Point),
+}
+
+/// Result of parsing buffer start
+enum Parsed {
+    Event(InputEvent, usize),
+    /// Unknown or broken input of this length
+    Skip(usize),
+    Incomplete,
+}
+
+/// Decodes terminal input into [`InputEvent`]s. See [module docs](self) for example.
+#[derive(Default)]
+pub struct InputDecoder {
+    buf: Vec<u8>,
+}
+
+impl InputDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends bytes read from terminal
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Is there unfinished input, like a part of escape sequence
+    pub fn is_pending(&self) -> bool {
+        !self.buf.is_empty()
+    }
+
+    /// Next complete event. Returns `None` if there is no input or it's incomplete.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<InputEvent> {
+        loop {
+            match parse(&self.buf) {
+                Parsed::Event(event, len) => {
+                    self.buf.drain(..len);
+                    return Some(event);
+                }
+                Parsed::Skip(len) => _ = self.buf.drain(..len),
+                Parsed::Incomplete => return None,
+            }
+        }
+    }
+
+    /// Like [`InputDecoder::next`], but incomplete input is not waited for. Call it when
+    /// no more input comes soon: unfinished escape sequence is an escape key.
+    pub fn flush(&mut self) -> Option<InputEvent> {
+        if let Some(event) = self.next() {
+            return Some(event);
+        }
+        match self.buf.first() {
+            Some(0x1b) => {
+                self.buf.remove(0);
+                Some(InputEvent::Key(KeyEvent::new(Key::Esc)))
+            }
+            // broken UTF-8
+            Some(_) => {
+                self.buf.clear();
+                None
+            }
+            None => None,
+        }
+    }
+}
+
+fn key(key: Key, len: usize) -> Parsed {
+    Parsed::Event(InputEvent::Key(KeyEvent::new(key)), len)
+}
+fn key_with(key: Key, mods: Modifiers, len: usize) -> Parsed {
+    Parsed::Event(InputEvent::Key(KeyEvent::with(key, mods)), len)
+}
+
+fn parse(buf: &[u8]) -> Parsed {
+    let Some(&first) = buf.first() else {
+        return Parsed::Incomplete;
+    };
+    match first {
+        0x1b => parse_escape(buf),
+        b'\r' | b'\n' => key(Key::Enter, 1),
+        b'\t' => key(Key::Tab, 1),
+        0x7f | 0x08 => key(Key::Backspace, 1),
+        0x00 => key_with(Key::Char(' '), ctrl(), 1),
+        0x01..=0x1a => key_with(Key::Char((b'a' + first - 1) as char), ctrl(), 1),
+        0x1c..=0x1f => Parsed::Skip(1),
+        _ => parse_utf8(buf),
+    }
+}
+
+fn ctrl() -> Modifiers {
+    Modifiers {
+        ctrl: true,
+        ..Default::default()
+    }
+}
+
+fn parse_utf8(buf: &[u8]) -> Parsed {
+    let len = match buf[0] {
+        0x00..=0x7f => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => return Parsed::Skip(1),
+    };
+    if buf.len() < len {
+        return Parsed::Incomplete;
+    }
+    match std::str::from_utf8(&buf[..len])
+        .ok()
+        .and_then(|s| s.chars().next())
+    {
+        Some(ch) => key(Key::Char(ch), len),
+        None => Parsed::Skip(1),
+    }
+}
+
+fn parse_escape(buf: &[u8]) -> Parsed {
+    match buf.get(1) {
+        None => Parsed::Incomplete,
+        Some(b'[') => parse_csi(buf),
+        Some(b'O') => match buf.get(2) {
+            None => Parsed::Incomplete,
+            Some(&b) => match ss3_key(b) {
+                Some(k) => key(k, 3),
+                None => Parsed::Skip(3),
+            },
+        },
+        // alt+key
+        Some(_) => match parse(&buf[1..]) {
+            Parsed::Event(InputEvent::Key(mut event), len) => {
+                event.mods.alt = true;
+                Parsed::Event(InputEvent::Key(event), len + 1)
+            }
+            Parsed::Event(_, len) | Parsed::Skip(len) => Parsed::Skip(len + 1),
+            Parsed::Incomplete => Parsed::Incomplete,
+        },
+    }
+}
+
+fn ss3_key(b: u8) -> Option<Key> {
+    Some(match b {
+        b'A' => Key::Up,
+        b'B' => Key::Down,
+        b'C' => Key::Right,
+        b'D' => Key::Left,
+        b'H' => Key::Home,
+        b'F' => Key::End,
+        b'P'..=b'S' => Key::F(b - b'P' + 1),
+        _ => return None,
+    })
+}
+
+/// Parses `ESC [ ...`
+fn parse_csi(buf: &[u8]) -> Parsed {
+    // X10 mouse: ESC [ M Cb Cx Cy
+    if buf.get(2) == Some(&b'M') {
+        return match buf.get(3..6) {
+            Some(&[b, x, y]) => mouse(
+                u16::from(b.saturating_sub(32)),
+                u16::from(x.saturating_sub(32)),
+                u16::from(y.saturating_sub(32)),
+                None,
+                6,
+            ),
+            _ => Parsed::Incomplete,
+        };
+    }
+
+    let Some(end) = buf.iter().skip(2).position(|b| (0x40..=0x7e).contains(b)) else {
+        return if buf.len() > MAX_SEQUENCE {
+            Parsed::Skip(buf.len())
+        } else {
+            Parsed::Incomplete
+        };
+    };
+    let len = end + 3;
+    let final_byte = buf[len - 1];
+    let body = &buf[2..len - 1];
+    let (private, body) = match body.first() {
+        Some(&p @ (b'<' | b'?' | b'>' | b'=')) => (Some(p), &body[1..]),
+        _ => (None, body),
+    };
+    let Some(params) = std::str::from_utf8(body).ok().map(|s| {
+        s.split(';')
+            .map(|p| p.parse::<u16>().ok())
+            .collect::<Vec<_>>()
+    }) else {
+        return Parsed::Skip(len);
+    };
+    let param = |i: usize| params.get(i).copied().flatten();
+
+    match (private, final_byte) {
+        // SGR mouse: ESC [ < Cb ; Cx ; Cy M/m
+        (Some(b'<'), b'M' | b'm') => match (param(0), param(1), param(2)) {
+            (Some(b), Some(x), Some(y)) => mouse(b, x, y, Some(final_byte == b'm'), len),
+            _ => Parsed::Skip(len),
+        },
+        (Some(_), _) => Parsed::Skip(len),
+        // window size: ESC [ 8 ; lines ; cols t, in-band resize: ESC [ 48 ; lines ; cols ; ... t
+        (None, b't') => match (param(0), param(1), param(2)) {
+            (Some(8 | 48), Some(lines), Some(cols)) => {
+                Parsed::Event(InputEvent::Resize(Point(cols, lines)), len)
+            }
+            _ => Parsed::Skip(len),
+        },
+        (None, b'Z') => key_with(
+            Key::Tab,
+            Modifiers {
+                shift: true,
+                ..Default::default()
+            },
+            len,
+        ),
+        (None, b'~') => {
+            let k = match param(0) {
+                Some(1 | 7) => Key::Home,
+                Some(2) => Key::Insert,
+                Some(3) => Key::Delete,
+                Some(4 | 8) => Key::End,
+                Some(5) => Key::PageUp,
+                Some(6) => Key::PageDown,
+                Some(n @ 11..=15) => Key::F((n - 10) as u8),
+                Some(n @ 17..=21) => Key::F((n - 11) as u8),
+                Some(n @ 23..=26) => Key::F((n - 12) as u8),
+                Some(n @ 28..=29) => Key::F((n - 13) as u8),
+                Some(n @ 31..=34) => Key::F((n - 14) as u8),
+                _ => return Parsed::Skip(len),
+            };
+            key_with(k, Modifiers::from_param(param(1).unwrap_or(1)), len)
+        }
+        (None, b) => match ss3_key(b) {
+            Some(k) => key_with(k, Modifiers::from_param(param(1).unwrap_or(1)), len),
+            None => Parsed::Skip(len),
+        },
+    }
+}
+
+/// Mouse event from xterm button code and 1-based position. `release` is `None` for
+/// X10 encoding, where release is a button code.
+fn mouse(code: u16, x: u16, y: u16, release: Option<bool>, len: usize) -> Parsed {
+    let mods = Modifiers {
+        shift: code & 4 != 0,
+        alt: code & 8 != 0,
+        ctrl: code & 16 != 0,
+    };
+    let button = match code & 3 {
+        0 => Some(MouseButton::Left),
+        1 => Some(MouseButton::Middle),
+        2 => Some(MouseButton::Right),
+        _ => None,
+    };
+    let kind = if code & 64 != 0 {
+        match code & 1 {
+            0 => MouseKind::ScrollUp,
+            _ => MouseKind::ScrollDown,
+        }
+    } else if code & 32 != 0 {
+        match button {
+            Some(button) => MouseKind::Drag(button),
+            None => MouseKind::Move,
+        }
+    } else {
+        match (release, button) {
+            (Some(true), button) | (None, button @ None) => MouseKind::Release(button),
+            (_, Some(button)) => MouseKind::Press(button),
+            (Some(false), None) => return Parsed::Skip(len),
+        }
+    };
+    let pos = Point(x.saturating_sub(1), y.saturating_sub(1));
+    Parsed::Event(InputEvent::Mouse(MouseEvent { kind, pos, mods }), len)
+}
+
+/// Result of [`InputReader::poll_event`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Poll {
+    Event(InputEvent),
+    /// Nothing came in time
+    Timeout,
+    /// Waiting was interrupted by signal, e.g. `SIGWINCH` (see
+    /// [`super::Context::check_resize`])
+    Interrupted,
+    /// End of input
+    Closed,
+}
+
+/// Reads [`InputEvent`]s from terminal.
+///
+/// Escape sequences and chars may be split between reads. Unfinished input is flushed
+/// (see [`InputDecoder::flush`]) when nothing follows it for a while or input ends, so
+/// lone escape key is not lost.
+pub struct InputReader<R: Read> {
+    inner: R,
+    decoder: InputDecoder,
+}
+
+impl<R: Read> InputReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            decoder: InputDecoder::new(),
+        }
+    }
+
+    /// Reads next event. Returns `None` on end of input. It can't wait for a while, so lone
+    /// escape key comes only with next input, use [`InputReader::poll_event`] if input
+    /// can be polled.
+    pub fn read_event(&mut self) -> io::Result<Option<InputEvent>> {
+        let buf = &mut [0; 64];
+        loop {
+            if let Some(event) = self.decoder.next() {
+                return Ok(Some(event));
+            }
+            let n = match self.inner.read(buf) {
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if n == 0 {
+                return Ok(self.decoder.flush());
+            }
+            self.decoder.feed(&buf[..n]);
+        }
+    }
+}
+
+impl<R: Read + AsRawFd> InputReader<R> {
+    /// Waits for next event no longer than `timeout`. Without timeout waits forever.
+    /// Unfinished input is flushed if its rest doesn't come soon.
+    pub fn poll_event(&mut self, timeout: Option<Duration>) -> io::Result<Poll> {
+        let deadline = timeout.map(|t| Instant::now() + t);
+        // not less than buffer of `Stdin`, so nothing stays there unseen by `poll`
+        let buf = &mut [0; 8192];
+        loop {
+            if let Some(event) = self.decoder.next() {
+                return Ok(Poll::Event(event));
+            }
+            let mut left = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+            let pending = self.decoder.is_pending();
+            if pending {
+                left = Some(left.map_or(FLUSH_DELAY, |l| l.min(FLUSH_DELAY)));
+            }
+            let readable = match wait_readable(self.inner.as_raw_fd(), left) {
+                Ok(readable) => readable,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => return Ok(Poll::Interrupted),
+                Err(e) => return Err(e),
+            };
+            if !readable {
+                if let Some(event) = self.decoder.flush() {
+                    return Ok(Poll::Event(event));
+                }
+                if pending && deadline.is_none_or(|d| Instant::now() < d) {
+                    continue;
+                }
+                return Ok(Poll::Timeout);
+            }
+            let n = match self.inner.read(buf) {
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if n == 0 {
+                return Ok(self.decoder.flush().map_or(Poll::Closed, Poll::Event));
+            }
+            self.decoder.feed(&buf[..n]);
+        }
+    }
+}
+
+/// Waits until `fd` is readable. Returns `false` on timeout and
+/// [`io::ErrorKind::Interrupted`] error on signal.
+fn wait_readable(fd: i32, timeout: Option<Duration>) -> io::Result<bool> {
+    let mut pollfd = libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let timeout = match timeout {
+        // round up, so deadline is not missed by less than a millisecond
+        Some(t) => (t.as_micros().div_ceil(1000)).min(i32::MAX as u128) as i32,
+        None => -1,
+    };
+    // SAFETY: `pollfd` is a valid array of one element
+    match unsafe { libc::poll(&mut pollfd, 1, timeout) } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => Ok(false),
+        _ => Ok(true),
+    }
+}
@@ -0,0 +1,514 @@
+//! Terminal User Interface
+//!
+//! [`Context`] writes to any [`Write`] and [`input::InputReader`] reads from any
+//! [`std::io::Read`], so the game can be played on the terminal of this process, on a pty
+//! or over a socket. Only the terminal of this process is set up by termios (see
+//! [`Context::init`]), other ones are expected to be set up by their side.
+//!
+//! # Example
+//! ```
+//! use ppl_game::{
+//!     game::Game,
+//!     ui::{Context as _, Point},
+//!     ui_impls::tui::{palette::{ColorDepth, Palette}, Context},
+//! };
+//!
+//! let ui = Context::with_output(Vec::new(), Point(80, 24), Palette::new(ColorDepth::Mono));
+//! let mut game = Game::new_with_seed(ui, 42);
+//! game.redraw_all().unwrap();
+//! game.handle.ui.apply().unwrap();
+//!
+//! let out = String::from_utf8_lossy(game.handle.ui.output());
+//! assert!(out.contains("-[ MAP ]-") && out.contains('@'));
+//! ```
+
+use std::{
+    io::{Stdout, Write},
+    mem::ManuallyDrop,
+    sync::atomic::Ordering,
+};
+
+use crate::ui::{self, Point};
+use layout::{Layout, Rect, MIN_SIZE};
+
+pub use super::layout::{self, DEFAULT_SIZE};
+use palette::{ColorDepth, Palette};
+use screen::{char_width, Cell, Screen, Style};
+use termios::Termios;
+
+pub mod input;
+pub mod palette;
+pub mod screen;
+mod signal;
+pub mod telnet;
+
+/// Init termios state and switches to alternate screen. Returns original [`Termios`] that
+/// can be passed to [`termios_restore`] later.
+///
+/// Reads block until at least one byte comes (`VMIN = 1`). To wait with timeout, as
+/// real-time mode does, use [`input::InputReader::poll_event`]: it reads only when input
+/// is ready.
+pub fn termios_init() -> Result<Termios, std::io::Error> {
+    let mut termios = Termios::from_fd(libc::STDIN_FILENO)?;
+    let result = termios;
+
+    use termios::*;
+
+    termios.c_lflag &= !(ECHO | ECHOE | ECHOK | ECHONL);
+    termios.c_lflag &= !ICANON;
+    // FIXME: oppsie
+    // termios.c_lflag &= ~(VSTOP | VSUSP | VQUIT);
+    termios.c_cc[VMIN] = 1;
+    termios.c_cc[VTIME] = 0;
+
+    termios::tcsetattr(libc::STDIN_FILENO, termios::TCSANOW, &termios)?;
+    signal::set_active(result, termios);
+    std::io::stdout().write_all(signal::ENTER_SEQ)?;
+    std::io::stdout().flush()?;
+
+    Ok(result)
+}
+
+/// Restores termios state and leaves alternate screen. See also [`termios_init`]
+pub fn termios_restore(v: Termios) -> Result<(), std::io::Error> {
+    signal::set_inactive();
+    std::io::stdout().write_all(signal::LEAVE_SEQ)?;
+    std::io::stdout().flush()?;
+    termios::tcsetattr(libc::STDIN_FILENO, termios::TCSANOW, &v)
+}
+
+/// Guard for terminal settings. On drop restores it.
+///
+/// # Example
+/// ```no_run
+/// use ppl_game::ui_impls::tui::TermiosGuard;
+///
+/// let value = 42;
+/// {
+///     let _guard = TermiosGuard::init(value).expect("termios");
+///     println!("this text written with tui termios settings");
+///     // *_guard drops*
+/// }
+/// println!("this is not");
+/// ```
+///
+/// # Notes
+/// On created this guard sets the main settings like "no echo" and
+/// cursor style and switches to alternate screen. On drop the normal
+/// screen with its scrollback comes back.
+///
+/// Drop doesn't happen on signals and with `panic = 'abort'`, terminal
+/// is restored there by handlers [`Context::init`] installs.
+pub struct TermiosGuard<T: ?Sized> {
+    _guard: TermiosInnerGuard,
+    pub v: T,
+}
+struct TermiosInnerGuard {
+    saved_state: Termios,
+}
+impl<T> TermiosGuard<T> {
+    /// Init termios state (by executing [`termios_init`]) and wraps `v` into guard.
+    pub fn init(v: T) -> Result<Self, std::io::Error> {
+        Ok(Self {
+            _guard: TermiosInnerGuard {
+                saved_state: termios_init()?,
+            },
+            v,
+        })
+    }
+
+    /// "Leaks" guard without restoring state.
+    pub fn leak(self) -> T {
+        _ = ManuallyDrop::new(self._guard);
+        self.v
+    }
+    /// Takes inner value and restore state.
+    pub fn into_inner(self) -> T {
+        self.v
+    }
+
+    /// Converts `TermiosGuard<T>` into `TermiosGuard<U>`
+    pub fn map<F, U>(self, f: F) -> TermiosGuard<U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        TermiosGuard {
+            _guard: self._guard,
+            v: f(self.v),
+        }
+    }
+}
+impl<T: ?Sized> std::ops::Deref for TermiosGuard<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.v
+    }
+}
+impl<T: ?Sized> std::ops::DerefMut for TermiosGuard<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.v
+    }
+}
+impl std::ops::Drop for TermiosInnerGuard {
+    fn drop(&mut self) {
+        // calculated
+        _ = termios_restore(self.saved_state);
+    }
+}
+
+/// Main struct of user interface. Implements [`crate::ui::Context`] trait
+///
+/// Fragments are placed by [`Layout`] of terminal size. On small terminal there is no
+/// layout: fragments are empty and "terminal too small" message is shown instead.
+///
+/// Fragments draw into [`Screen`], terminal is updated on [`ui::Context::apply`] by writing
+/// to `W`. Colors depend on [`ColorDepth`] of terminal.
+pub struct Context<W: Write = Stdout> {
+    out: W,
+    layout: Option<Layout>,
+    screen: Screen,
+    palette: Palette,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: Write> ui::Context for Context<W> {
+    type Error = std::io::Error;
+    type Status<'a>
+        = Fragment<'a, TextTy>
+    where
+        W: 'a;
+    type Main<'a>
+        = Fragment<'a, BlockTy>
+    where
+        W: 'a;
+    type Lore<'a>
+        = Fragment<'a, TextTy>
+    where
+        W: 'a;
+
+    fn status(&mut self) -> Self::Status<'_> {
+        let rect = self.rect(|l| l.status);
+        Fragment::new(TextTy, &mut self.screen, self.palette, rect)
+    }
+    fn main(&mut self) -> Self::Main<'_> {
+        let rect = self.rect(|l| l.main);
+        Fragment::new(BlockTy, &mut self.screen, self.palette, rect)
+    }
+    fn lore(&mut self) -> Self::Lore<'_> {
+        let rect = self.rect(|l| l.lore);
+        Fragment::new(TextTy, &mut self.screen, self.palette, rect)
+    }
+    fn main_size(&self) -> Point {
+        self.rect(|l| l.main).size
+    }
+
+    /// Writes changes of screen to terminal at once
+    fn apply(&mut self) -> Result<(), Self::Error> {
+        let mut out = Vec::new();
+        self.screen.render(&mut out);
+        self.out.write_all(&out)?;
+        self.out.flush()
+    }
+}
+
+/// Size of terminal (cols; lines), [`DEFAULT_SIZE`] if it's unknown
+pub fn terminal_size() -> Point {
+    // SAFETY: `winsize` is plain data, `ioctl` only writes it
+    let size = unsafe {
+        let mut size: libc::winsize = std::mem::zeroed();
+        (libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) == 0).then_some(size)
+    };
+    match size {
+        Some(size) if size.ws_col != 0 && size.ws_row != 0 => Point(size.ws_col, size.ws_row),
+        _ => DEFAULT_SIZE,
+    }
+}
+
+/// Largest terminal (cols; lines) screen is kept for, bigger one uses only this part.
+/// Size comes from remote clients too, so it must not allocate whatever they ask.
+pub const MAX_SIZE: Point = Point(512, 256);
+
+/// `size` that is not larger than [`MAX_SIZE`]
+fn limit_size(Point(cols, lines): Point) -> Point {
+    Point(cols.min(MAX_SIZE.0), lines.min(MAX_SIZE.1))
+}
+
+impl Context {
+    /// Creates new context for current terminal size and color depth. If you need to init
+    /// interface see [`Context::init`]
+    pub fn new() -> Self {
+        Self::with_palette(Palette::new(ColorDepth::detect()))
+    }
+    /// Creates new context for current terminal size with given colors
+    pub fn with_palette(palette: Palette) -> Self {
+        Self::with_output(std::io::stdout(), terminal_size(), palette)
+    }
+    /// Init TUI. Switches to alternate screen, sets termios state and starts watching
+    /// terminal size (see [`Context::check_resize`]). Terminal is restored on `SIGINT`,
+    /// `SIGTERM`, `SIGTSTP` and panic.
+    pub fn init() -> Result<TermiosGuard<Self>, std::io::Error> {
+        signal::install()?;
+        TermiosGuard::init(Self::new())
+    }
+
+    /// Applies new terminal size if it was changed (`SIGWINCH` came) or terminal was set up
+    /// again after process continued. Returns `true` if screen was cleared and everything
+    /// must be redrawn.
+    pub fn check_resize(&mut self) -> bool {
+        if !signal::RESIZED.swap(false, Ordering::SeqCst) {
+            return false;
+        }
+        self.resize(terminal_size());
+        true
+    }
+}
+
+impl<W: Write> Context<W> {
+    /// Creates context that writes to `out`, terminal of `size` is expected there. Nothing
+    /// is written until [`ui::Context::apply`]: first one clears terminal.
+    ///
+    /// Terminal is not set up. See [`Context::enter_screen`] to switch to alternate
+    /// screen.
+    ///
+    /// Size is limited by [`MAX_SIZE`], like in [`Context::resize`].
+    pub fn with_output(out: W, size: Point, palette: Palette) -> Self {
+        let size = limit_size(size);
+        let mut context = Self {
+            out,
+            layout: Layout::new(size),
+            screen: Screen::new(size),
+            palette,
+        };
+        context.draw_borders();
+        context
+    }
+
+    /// Switches terminal to alternate screen and hides cursor, like [`termios_init`] does
+    /// for terminal of this process
+    pub fn enter_screen(&mut self) -> Result<(), std::io::Error> {
+        self.out.write_all(signal::ENTER_SEQ)?;
+        self.out.flush()
+    }
+    /// Gives back normal screen and cursor, see [`Context::enter_screen`]
+    pub fn leave_screen(&mut self) -> Result<(), std::io::Error> {
+        self.out.write_all(signal::LEAVE_SEQ)?;
+        self.out.flush()
+    }
+
+    /// Where terminal output goes
+    pub fn output(&self) -> &W {
+        &self.out
+    }
+    /// Where terminal output goes
+    pub fn output_mut(&mut self) -> &mut W {
+        &mut self.out
+    }
+    /// Takes output back
+    pub fn into_output(self) -> W {
+        self.out
+    }
+
+    /// Current layout, `None` if terminal is too small
+    pub fn layout(&self) -> Option<&Layout> {
+        self.layout.as_ref()
+    }
+
+    fn rect(&self, f: impl FnOnce(&Layout) -> Rect) -> Rect {
+        self.layout.as_ref().map(f).unwrap_or_default()
+    }
+
+    /// Sets terminal size, clears screen and draws borders. Everything must be redrawn
+    /// after it.
+    ///
+    /// Size larger than [`MAX_SIZE`] is cut to it, so client can't make server allocate
+    /// screen of any size:
+    /// ```
+    /// use std::{io::Write, net::{TcpListener, TcpStream}};
+    /// use ppl_game::{
+    ///     ui::Point,
+    ///     ui_impls::tui::{
+    ///         input::{InputEvent, InputReader, Key, KeyEvent},
+    ///         palette::{ColorDepth, Palette},
+    ///         telnet::Telnet,
+    ///         Context, MAX_SIZE,
+    ///     },
+    /// };
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    /// let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    /// let (server, _) = listener.accept().unwrap();
+    /// let mut ui = Context::with_output(
+    ///     server.try_clone().unwrap(),
+    ///     Point(80, 24),
+    ///     Palette::new(ColorDepth::Mono),
+    /// );
+    /// let mut input = InputReader::new(Telnet::new(server));
+    ///
+    /// // client tells it's 65535x65535 (`0xFF` doubled in subnegotiation) and presses `w`
+    /// let naws = [255, 250, 31, 255, 255, 255, 255, 255, 255, 255, 255, 255, 240];
+    /// client.write_all(&naws).unwrap();
+    /// client.write_all(b"w").unwrap();
+    ///
+    /// let Some(InputEvent::Resize(size)) = input.read_event().unwrap() else {
+    ///     panic!("no resize");
+    /// };
+    /// assert_eq!(size, Point(65535, 65535));
+    /// ui.resize(size);
+    /// assert_eq!(ui.layout().unwrap().size, MAX_SIZE);
+    ///
+    /// // session goes on
+    /// let key = input.read_event().unwrap();
+    /// assert_eq!(key, Some(InputEvent::Key(KeyEvent::new(Key::Char('w')))));
+    /// ```
+    pub fn resize(&mut self, size: Point) {
+        let size = limit_size(size);
+        self.layout = Layout::new(size);
+        self.screen.resize(size);
+        self.draw_borders();
+    }
+
+    /// Draw borders between fragments or "terminal too small" message
+    fn draw_borders(&mut self) {
+        let screen = &mut self.screen;
+        let Point(cols, lines) = screen.size();
+        let Some(layout) = &self.layout else {
+            let need = format!("need {}x{}, have {cols}x{lines}", MIN_SIZE.0, MIN_SIZE.1);
+            for (y, line) in ["terminal too small", &need].into_iter().enumerate() {
+                screen.put_str(Point(0, y as u16), cols, line, Style::PLAIN);
+            }
+            return;
+        };
+        let Rect {
+            pos: Point(_, top),
+            size: Point(width, lines),
+        } = layout.main;
+        let border = top - 1;
+        let mut line: String = "-[ MAP ]-".chars().take(width as usize).collect();
+        line.extend(std::iter::repeat_n('-', width as usize - line.len()));
+        line += "+";
+        let rest = (cols - width - 1) as usize;
+        line.extend("-[ LORE ]".chars().chain(std::iter::repeat('-')).take(rest));
+        screen.put_str(Point(0, border), cols, &line, Style::PLAIN);
+        for y in top..top + lines {
+            screen.put(
+                Point(width, y),
+                Cell {
+                    ch: '|',
+                    style: Style::PLAIN,
+                },
+            );
+        }
+    }
+}
+
+/// Fragment of interface in rectangle of [`Layout`]. Implements [`crate::ui::Fragment`]
+/// etc... Anything out of rectangle is not drawn.
+pub struct Fragment<'context, S: FragmentType> {
+    _ty: S,
+    screen: &'context mut Screen,
+    palette: Palette,
+    rect: Rect,
+    /// Cursor position in fragment
+    pos: Point,
+    /// Style of text
+    style: Style,
+}
+
+/// Type of [`Fragment`].
+pub trait FragmentType {}
+
+/// Type of [`Fragment`].
+pub struct BlockTy;
+impl FragmentType for BlockTy {}
+
+/// Type of [`Fragment`].
+pub struct TextTy;
+impl FragmentType for TextTy {}
+
+impl<'context, S: FragmentType> Fragment<'context, S> {
+    /// Creates fragment with position in its top left corner.
+    fn new(ty: S, screen: &'context mut Screen, palette: Palette, rect: Rect) -> Self {
+        Self {
+            _ty: ty,
+            screen,
+            palette,
+            rect,
+            pos: Point(0, 0),
+            style: Style::PLAIN,
+        }
+    }
+
+    /// Screen point of fragment point, `None` if it's out of fragment
+    fn screen_pos(&self, pos: Point) -> Option<Point> {
+        self.rect
+            .contains(pos)
+            .then_some(Point(self.rect.pos.0 + pos.0, self.rect.pos.1 + pos.1))
+    }
+}
+
+impl<'context, S: FragmentType> ui::Fragment for Fragment<'context, S> {
+    type Error = std::io::Error;
+
+    fn set_pos(&mut self, pos: Point) -> Result<(), std::io::Error> {
+        self.pos = pos;
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<(), Self::Error> {
+        self.screen.clear_rect(self.rect);
+        self.pos = Point(0, 0);
+        Ok(())
+    }
+
+    fn clear_line(&mut self, line: u16) -> Result<(), Self::Error> {
+        if line < self.rect.size.1 {
+            self.screen.clear_rect(Rect {
+                pos: Point(self.rect.pos.0, self.rect.pos.1 + line),
+                size: Point(self.rect.size.0, 1),
+            });
+        }
+        self.set_line(line)
+    }
+}
+impl<'context> ui::BlockFragment for Fragment<'context, BlockTy> {
+    fn put_block(&mut self, block: ui::BlockTy) -> Result<(), std::io::Error> {
+        let cell = self.palette.block(block);
+        if let Some(pos) = self.screen_pos(self.pos) {
+            self.screen.put(pos, cell);
+        }
+        self.pos.0 = self.pos.0.saturating_add(1);
+        Ok(())
+    }
+}
+impl<'context> ui::TextFragment for Fragment<'context, TextTy> {
+    fn set_color(&mut self, color: ui::Color) -> Result<(), Self::Error> {
+        self.style = self.palette.color(color);
+        Ok(())
+    }
+
+    fn put_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        let mut lines = s.split('\n').peekable();
+        while let Some(line) = lines.next() {
+            match self.screen_pos(self.pos) {
+                Some(pos) => {
+                    let limit = self.rect.pos.0 + self.rect.size.0;
+                    let end = self.screen.put_str(pos, limit, line, self.style);
+                    self.pos.0 = end - self.rect.pos.0;
+                }
+                None => {
+                    let width: u16 = line.chars().map(char_width).sum();
+                    self.pos.0 = self.pos.0.saturating_add(width);
+                }
+            }
+            if lines.peek().is_some() {
+                self.pos = Point(0, self.pos.1.saturating_add(1));
+            }
+        }
+        Ok(())
+    }
+}
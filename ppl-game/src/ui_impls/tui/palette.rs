@@ -0,0 +1,176 @@
+//! Colors of blocks and text for terminal color depth
+//!
+//! Depth is detected from environment: `NO_COLOR` (<https://no-color.org>) or `TERM=dumb`
+//! turn colors off, `COLORTERM=truecolor` enables 24-bit colors and `TERM=*256color*`
+//! enables 256 colors. Without colors blocks differ by bold, underline and reverse.
+//!
+//! # Example
+//! ```
+//! use ppl_game::{ui::BlockTy, ui_impls::tui::palette::{ColorDepth, Palette}};
+//!
+//! let depth = ColorDepth::from_env(None, Some("xterm-256color"), None);
+//! assert_eq!(depth, ColorDepth::Ansi256);
+//! assert_eq!(ColorDepth::from_env(Some("1"), Some("xterm-256color"), None), ColorDepth::Mono);
+//! assert_eq!(ColorDepth::from_env(None, Some("xterm"), Some("truecolor")), ColorDepth::TrueColor);
+//!
+//! // blocks stay distinct without colors
+//! let mono = Palette::new(ColorDepth::Mono);
+//! assert_ne!(mono.block(BlockTy::Wall), mono.block(BlockTy::Wheat));
+//! ```
+
+use crate::{
+    assets::registry::Registry,
+    ui::{BlockTy, Color},
+};
+
+use super::screen::{Cell, Fg, Style};
+
+/// Colors terminal supports
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorDepth {
+    /// No colors, only attributes
+    Mono,
+    /// 8 colors and their bright variants
+    Ansi16,
+    /// xterm 256 colors
+    Ansi256,
+    /// 24-bit colors
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Color depth of current terminal by `NO_COLOR`, `TERM` and `COLORTERM`
+    pub fn detect() -> Self {
+        let var = |name| std::env::var(name).ok();
+        Self::from_env(
+            var("NO_COLOR").as_deref(),
+            var("TERM").as_deref(),
+            var("COLORTERM").as_deref(),
+        )
+    }
+
+    /// Color depth by values of `NO_COLOR`, `TERM` and `COLORTERM`
+    pub fn from_env(no_color: Option<&str>, term: Option<&str>, colorterm: Option<&str>) -> Self {
+        if no_color.is_some_and(|v| !v.is_empty()) {
+            return Self::Mono;
+        }
+        let term = match term {
+            None | Some("" | "dumb") => return Self::Mono,
+            Some(term) => term,
+        };
+        if matches!(colorterm, Some("truecolor" | "24bit")) || term.ends_with("-direct") {
+            Self::TrueColor
+        } else if term.contains("256color") {
+            Self::Ansi256
+        } else {
+            Self::Ansi16
+        }
+    }
+}
+
+/// One color in all depths
+#[derive(Clone, Copy)]
+struct Ink {
+    /// One of 16 colors
+    ansi: u8,
+    /// One of 256 colors
+    indexed: u8,
+    rgb: (u8, u8, u8),
+}
+
+const fn ink(ansi: u8, indexed: u8, rgb: (u8, u8, u8)) -> Ink {
+    Ink { ansi, indexed, rgb }
+}
+
+const RED: Ink = ink(1, 196, (255, 60, 60));
+const GREEN: Ink = ink(2, 34, (0, 175, 0));
+const DARK_GREEN: Ink = ink(2, 28, (0, 135, 0));
+const BRIGHT_GREEN: Ink = ink(10, 118, (135, 255, 0));
+const YELLOW: Ink = ink(3, 178, (215, 175, 0));
+const BRIGHT_YELLOW: Ink = ink(11, 220, (255, 215, 0));
+const STRAW: Ink = ink(3, 137, (175, 135, 95));
+const BROWN: Ink = ink(4, 130, (175, 95, 0));
+const BLUE: Ink = ink(4, 33, (0, 135, 255));
+const MAGENTA: Ink = ink(5, 170, (215, 95, 215));
+const CYAN: Ink = ink(6, 44, (0, 215, 215));
+const GRAY: Ink = ink(8, 244, (128, 128, 128));
+const DARK_GRAY: Ink = ink(8, 242, (108, 108, 108));
+
+/// Maps blocks and colors of [`crate::ui`] to cells and styles for color depth
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Palette {
+    pub depth: ColorDepth,
+}
+
+impl Palette {
+    pub fn new(depth: ColorDepth) -> Self {
+        Self { depth }
+    }
+
+    /// Style with color, `None` in monochrome
+    fn ink(self, ink: Ink) -> Style {
+        let fg = match self.depth {
+            ColorDepth::Mono => None,
+            ColorDepth::Ansi16 => Some(Fg::Ansi(ink.ansi)),
+            ColorDepth::Ansi256 => Some(Fg::Indexed(ink.indexed)),
+            ColorDepth::TrueColor => Some(Fg::Rgb(ink.rgb.0, ink.rgb.1, ink.rgb.2)),
+        };
+        Style { fg, ..Style::PLAIN }
+    }
+
+    /// Glyph and style of block
+    pub fn block(self, block: BlockTy) -> Cell {
+        use BlockTy::*;
+        let mono = self.depth == ColorDepth::Mono;
+        let (ch, style) = match block {
+            Air => (' ', Style::PLAIN),
+
+            Player => ('@', self.ink(BRIGHT_GREEN).bold().reverse_if(mono)),
+            OtherPlayer => ('P', self.ink(CYAN).bold().underline_if(mono)),
+            NPCFarmer => ('&', self.ink(YELLOW).bold()),
+
+            Wheat => ('#', self.ink(YELLOW).bold_if(mono)),
+            GrowingWheat => ('+', self.ink(GREEN)),
+            DryWheat => (',', self.ink(STRAW)),
+
+            Tree => ('^', self.ink(DARK_GREEN)),
+
+            Wall => ('#', self.ink(GRAY).reverse_if(mono)),
+            WallDoor => ('-', self.ink(GRAY).reverse_if(mono)),
+
+            Water => ('%', self.ink(BLUE).underline_if(mono)),
+            BridgeV => ('|', self.ink(BROWN).underline_if(mono)),
+            BridgeH => ('-', self.ink(BROWN).underline_if(mono)),
+
+            Custom(name) => match Registry::get().block(name) {
+                Some(def) => (def.glyph, self.color(def.color)),
+                None => ('?', Style::PLAIN),
+            },
+        };
+        Cell { ch, style }
+    }
+
+    /// Style of text color
+    pub fn color(self, color: Color) -> Style {
+        use Color::*;
+        let mono = self.depth == ColorDepth::Mono;
+        match color {
+            Normal => Style::PLAIN,
+            Disabled => self.ink(DARK_GRAY),
+            RareItem => self.ink(BLUE).underline_if(mono),
+            SpecialItem => self.ink(MAGENTA).underline_if(mono).bold_if(mono),
+
+            GrowingWheatBlock => self.ink(GREEN),
+            WheatBlock => self.ink(BRIGHT_YELLOW).bold_if(mono),
+            WaterBlock => self.ink(BLUE).underline_if(mono),
+
+            Health => self.ink(RED).bold(),
+            Hunger => self.ink(YELLOW),
+            XP => self.ink(CYAN),
+            Gold => self.ink(BRIGHT_YELLOW),
+            Wheat => self.ink(BRIGHT_YELLOW).bold(),
+            Water => self.ink(BLUE).bold(),
+            MaxValue => self.ink(GREEN).underline_if(mono),
+        }
+    }
+}
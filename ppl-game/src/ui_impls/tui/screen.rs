@@ -0,0 +1,304 @@
+//! Double-buffered screen
+//!
+//! Fragments draw into back buffer of [`Screen`]. [`Screen::render`] compares it with front
+//! buffer (what terminal shows) and writes only changed cells, so nothing flickers.
+//!
+//! # Example
+//! ```
+//! use ppl_game::{ui::Point, ui_impls::tui::screen::{Cell, Fg, Screen, Style}};
+//!
+//! let mut screen = Screen::new(Point(10, 2));
+//! screen.render(&mut Vec::new()); // first render clears terminal
+//!
+//! let style = Style { fg: Some(Fg::Ansi(10)), ..Style::PLAIN }.bold();
+//! screen.put(Point(2, 1), Cell { ch: '@', style });
+//! let mut out = Vec::new();
+//! screen.render(&mut out);
+//! assert_eq!(out, b"\x1b[2;3H\x1b[0;1;92m@\x1b[0m");
+//!
+//! // nothing changed, nothing to write
+//! screen.put(Point(2, 1), Cell { ch: '@', style });
+//! out.clear();
+//! screen.render(&mut out);
+//! assert!(out.is_empty());
+//! ```
+
+use std::io::Write;
+
+use crate::ui::Point;
+
+use super::layout::Rect;
+
+/// Foreground color, see [`super::palette`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Fg {
+    /// One of 16 colors, 8-15 are bright ones
+    Ansi(u8),
+    /// One of 256 colors
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+/// Style of cell
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Style {
+    /// `None` is default color
+    pub fg: Option<Fg>,
+    pub bold: bool,
+    pub underline: bool,
+    pub reverse: bool,
+}
+
+impl Style {
+    /// Default color without attributes
+    pub const PLAIN: Style = Style {
+        fg: None,
+        bold: false,
+        underline: false,
+        reverse: false,
+    };
+
+    /// Same style, but bold
+    pub const fn bold(self) -> Self {
+        Self { bold: true, ..self }
+    }
+    /// Bold if `cond`
+    pub const fn bold_if(self, cond: bool) -> Self {
+        Self {
+            bold: self.bold || cond,
+            ..self
+        }
+    }
+    /// Underlined if `cond`
+    pub const fn underline_if(self, cond: bool) -> Self {
+        Self {
+            underline: self.underline || cond,
+            ..self
+        }
+    }
+    /// With swapped colors if `cond`
+    pub const fn reverse_if(self, cond: bool) -> Self {
+        Self {
+            reverse: self.reverse || cond,
+            ..self
+        }
+    }
+
+    /// Writes SGR sequence of style
+    fn write_sgr(self, out: &mut Vec<u8>) {
+        out.extend_from_slice(b"\x1b[0");
+        if self.bold {
+            out.extend_from_slice(b";1");
+        }
+        if self.underline {
+            out.extend_from_slice(b";4");
+        }
+        if self.reverse {
+            out.extend_from_slice(b";7");
+        }
+        match self.fg {
+            Some(Fg::Ansi(c @ 0..=7)) => _ = write!(out, ";{}", 30 + c),
+            Some(Fg::Ansi(c)) => _ = write!(out, ";{}", 90 + (c & 7)),
+            Some(Fg::Indexed(c)) => _ = write!(out, ";38;5;{c}"),
+            Some(Fg::Rgb(r, g, b)) => _ = write!(out, ";38;2;{r};{g};{b}"),
+            None => {}
+        }
+        out.push(b'm');
+    }
+}
+
+/// One cell of screen
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cell {
+    pub ch: char,
+    pub style: Style,
+}
+
+impl Cell {
+    /// Empty cell
+    pub const BLANK: Cell = Cell {
+        ch: ' ',
+        style: Style::PLAIN,
+    };
+    /// Right half of wide char, it's not written
+    const WIDE_TAIL: Cell = Cell {
+        ch: '\0',
+        style: Cell::BLANK.style,
+    };
+}
+
+/// Columns char takes in terminal: 2 for wide (CJK, emoji), 1 for others
+pub fn char_width(ch: char) -> u16 {
+    match ch as u32 {
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+/// Unchanged cells rewritten instead of moving cursor over them, if they have same style.
+/// Cursor movement takes about as many bytes.
+const MAX_GAP: usize = 8;
+
+/// Back and front buffers of terminal
+pub struct Screen {
+    size: Point,
+    back: Vec<Cell>,
+    front: Vec<Cell>,
+    /// Terminal must be cleared before next render
+    clear: bool,
+}
+
+impl Screen {
+    /// Creates blank screen, terminal is cleared on first render
+    pub fn new(size: Point) -> Self {
+        let cells = vec![Cell::BLANK; size.0 as usize * size.1 as usize];
+        Self {
+            size,
+            back: cells.clone(),
+            front: cells,
+            clear: true,
+        }
+    }
+
+    /// Size of screen (cols; lines)
+    pub fn size(&self) -> Point {
+        self.size
+    }
+
+    /// Changes size, makes screen blank and clears terminal on next render
+    pub fn resize(&mut self, size: Point) {
+        *self = Self::new(size);
+    }
+
+    /// Cell in back buffer
+    pub fn get(&self, pos: Point) -> Option<Cell> {
+        self.index(pos).map(|i| self.back[i])
+    }
+
+    /// Puts cell into back buffer. Does nothing if `pos` is out of screen. Other half of
+    /// overwritten wide char is blanked.
+    ///
+    /// # Example
+    /// ```
+    /// use ppl_game::{ui::Point, ui_impls::tui::screen::{Cell, Screen, Style}};
+    ///
+    /// let mut screen = Screen::new(Point(4, 1));
+    /// screen.put_str(Point(0, 0), 4, "日本", Style::PLAIN);
+    /// screen.put(Point(0, 0), Cell { ch: 'a', style: Style::PLAIN });
+    /// screen.put(Point(3, 0), Cell { ch: 'b', style: Style::PLAIN });
+    /// let row: String = (0..4).map(|x| screen.get(Point(x, 0)).unwrap().ch).collect();
+    /// assert_eq!(row, "a  b");
+    /// ```
+    pub fn put(&mut self, pos: Point, cell: Cell) {
+        let Some(i) = self.index(pos) else {
+            return;
+        };
+        let old = self.back[i];
+        if old == Cell::WIDE_TAIL && cell != Cell::WIDE_TAIL && pos.0 > 0 {
+            self.back[i - 1] = Cell::BLANK;
+        } else if char_width(old.ch) == 2 && self.back.get(i + 1) == Some(&Cell::WIDE_TAIL) {
+            self.back[i + 1] = Cell::BLANK;
+        }
+        self.back[i] = cell;
+    }
+
+    /// Puts chars of `s` from `pos` but not further than `limit` column (exclusive).
+    /// Wide chars take two cells. Returns column after the last char, clipped ones
+    /// counted too.
+    pub fn put_str(&mut self, pos: Point, limit: u16, s: &str, style: Style) -> u16 {
+        let Point(mut x, y) = pos;
+        let limit = limit.min(self.size.0);
+        for ch in s.chars() {
+            let width = char_width(ch);
+            if x.saturating_add(width) <= limit {
+                self.put(Point(x, y), Cell { ch, style });
+                if width == 2 {
+                    self.put(Point(x + 1, y), Cell::WIDE_TAIL);
+                }
+            }
+            x = x.saturating_add(width);
+        }
+        x
+    }
+
+    /// Fills rectangle with blank cells
+    pub fn clear_rect(&mut self, rect: Rect) {
+        for y in rect.pos.1..rect.pos.1.saturating_add(rect.size.1) {
+            for x in rect.pos.0..rect.pos.0.saturating_add(rect.size.0) {
+                self.put(Point(x, y), Cell::BLANK);
+            }
+        }
+    }
+
+    /// Writes changes since last render to `out` and makes them shown
+    pub fn render(&mut self, out: &mut Vec<u8>) {
+        if self.clear {
+            out.extend_from_slice(b"\x1b[0m\x1b[H\x1b[2J");
+            self.front.fill(Cell::BLANK);
+            self.clear = false;
+        }
+        let width = self.size.0 as usize;
+        // position where terminal cursor is, if known
+        let mut cursor = None;
+        let mut style = None;
+        for i in 0..self.back.len() {
+            let back = self.back[i];
+            if back == self.front[i] {
+                continue;
+            }
+            self.front[i] = back;
+            if back == Cell::WIDE_TAIL {
+                continue;
+            }
+            match cursor {
+                Some(c) if c == i => {}
+                Some(c) if c < i && i - c <= MAX_GAP && c / width == i / width => {
+                    let gap = &self.back[c..i];
+                    if gap
+                        .iter()
+                        .all(|g| Some(g.style) == style && char_width(g.ch) == 1)
+                    {
+                        gap.iter().for_each(|g| write_char(out, g.ch));
+                    } else {
+                        _ = write!(out, "\x1b[{};{}H", i / width + 1, i % width + 1);
+                    }
+                }
+                _ => _ = write!(out, "\x1b[{};{}H", i / width + 1, i % width + 1),
+            }
+            if style != Some(back.style) {
+                back.style.write_sgr(out);
+                style = Some(back.style);
+            }
+            write_char(out, back.ch);
+            let next = i + char_width(back.ch) as usize;
+            // cursor doesn't go to next line at the end of line
+            cursor = (!next.is_multiple_of(width)).then_some(next);
+        }
+        if style.is_some_and(|s| s != Style::PLAIN) {
+            out.extend_from_slice(b"\x1b[0m");
+        }
+    }
+
+    fn index(&self, Point(x, y): Point) -> Option<usize> {
+        (x < self.size.0 && y < self.size.1)
+            .then_some(y as usize * self.size.0 as usize + x as usize)
+    }
+}
+
+fn write_char(out: &mut Vec<u8>, ch: char) {
+    let mut buf = [0; 4];
+    out.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+}
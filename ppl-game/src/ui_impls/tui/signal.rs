@@ -0,0 +1,168 @@
+//! Signals and panics
+//!
+//! Terminal is given back in the state it was before [`super::termios_init`] when process
+//! is killed by `SIGINT` or `SIGTERM`, stopped by `SIGTSTP` (`^Z`) or panics, even with
+//! `panic = 'abort'`. When stopped process continues, terminal is set up again and
+//! [`super::Context::check_resize`] asks to redraw everything.
+//!
+//! Signal handlers only use async-signal-safe calls: `write(2)`, `tcsetattr(3)` and
+//! `raise(3)`.
+
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+};
+
+use termios::Termios;
+
+/// Enters alternate screen, clears it and hides cursor
+pub(super) const ENTER_SEQ: &[u8] = b"\x1b[?1049h\x1b[H\x1b[2J\x1b[?25l";
+/// Resets style, shows cursor and leaves alternate screen
+pub(super) const LEAVE_SEQ: &[u8] = b"\x1b[0m\x1b[?25h\x1b[?1049l";
+
+/// Set when terminal size changed or terminal was set up again, so screen must be
+/// redrawn
+pub(super) static RESIZED: AtomicBool = AtomicBool::new(false);
+
+/// Terminal is set up for game
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// States of terminal set by first [`super::termios_init`]
+struct States {
+    /// Before game
+    saved: Termios,
+    /// For game
+    game: Termios,
+}
+static STATES: OnceLock<States> = OnceLock::new();
+
+/// Remembers states of terminal before and for game. Terminal is set up for game.
+pub(super) fn set_active(saved: Termios, game: Termios) {
+    STATES.get_or_init(|| States { saved, game });
+    ACTIVE.store(true, Ordering::SeqCst);
+}
+
+/// Terminal is restored, nothing to do on signals
+pub(super) fn set_inactive() {
+    ACTIVE.store(false, Ordering::SeqCst);
+}
+
+/// Installs handlers of `SIGWINCH`, `SIGINT`, `SIGTERM`, `SIGTSTP`, `SIGCONT` and panic
+/// hook. It's done once, next calls do nothing.
+pub(super) fn install() -> Result<(), io::Error> {
+    static INSTALLED: AtomicBool = AtomicBool::new(false);
+    if INSTALLED.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+    set_handler(libc::SIGWINCH, on_resize)?;
+    set_handler(libc::SIGINT, on_terminate)?;
+    set_handler(libc::SIGTERM, on_terminate)?;
+    set_handler(libc::SIGTSTP, on_stop)?;
+    set_handler(libc::SIGCONT, on_continue)?;
+
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // message must go to the normal screen, not to alternate one
+        restore();
+        hook(info);
+    }));
+    Ok(())
+}
+
+/// Sets `handler` for `signal`
+fn set_handler(signal: libc::c_int, handler: extern "C" fn(libc::c_int)) -> io::Result<()> {
+    // SAFETY: handlers only use async-signal-safe functions
+    unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = handler as libc::sighandler_t;
+        // no `SA_RESTART`: waiting for input is interrupted, so signal is seen at once
+        action.sa_flags = 0;
+        libc::sigemptyset(&mut action.sa_mask);
+        if libc::sigaction(signal, &action, std::ptr::null_mut()) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Gives terminal back if it's set up for game
+fn restore() {
+    if !ACTIVE.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    if let Some(states) = STATES.get() {
+        write_raw(LEAVE_SEQ);
+        _ = termios::tcsetattr(libc::STDIN_FILENO, termios::TCSANOW, &states.saved);
+    }
+}
+
+/// Sets terminal up for game again
+fn reinit() {
+    if let Some(states) = STATES.get() {
+        _ = termios::tcsetattr(libc::STDIN_FILENO, termios::TCSANOW, &states.game);
+        write_raw(ENTER_SEQ);
+        ACTIVE.store(true, Ordering::SeqCst);
+        RESIZED.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Writes to stdout without buffers and locks
+fn write_raw(mut bytes: &[u8]) {
+    while !bytes.is_empty() {
+        // SAFETY: pointer and length are of valid slice
+        let n = unsafe { libc::write(libc::STDOUT_FILENO, bytes.as_ptr().cast(), bytes.len()) };
+        if n <= 0 {
+            if n < 0 && io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return;
+        }
+        bytes = &bytes[n as usize..];
+    }
+}
+
+/// Raises `signal` with default action
+fn raise_default(signal: libc::c_int) {
+    // SAFETY: all functions are async-signal-safe
+    unsafe {
+        libc::signal(signal, libc::SIG_DFL);
+        // signal is blocked while its handler runs
+        let mut set: libc::sigset_t = std::mem::zeroed();
+        libc::sigemptyset(&mut set);
+        libc::sigaddset(&mut set, signal);
+        libc::pthread_sigmask(libc::SIG_UNBLOCK, &set, std::ptr::null_mut());
+        libc::raise(signal);
+    }
+}
+
+extern "C" fn on_resize(_: libc::c_int) {
+    RESIZED.store(true, Ordering::SeqCst);
+}
+
+/// Restores terminal and dies of signal
+extern "C" fn on_terminate(signal: libc::c_int) {
+    restore();
+    raise_default(signal);
+}
+
+/// Restores terminal and stops, sets it up again when continued
+extern "C" fn on_stop(signal: libc::c_int) {
+    let active = ACTIVE.load(Ordering::SeqCst);
+    restore();
+    raise_default(signal);
+    // continued
+    if active {
+        reinit();
+    }
+    _ = set_handler(signal, on_stop);
+}
+
+/// Sets terminal up again if process was stopped without [`on_stop`] (by `SIGSTOP`)
+extern "C" fn on_continue(_: libc::c_int) {
+    if ACTIVE.load(Ordering::SeqCst) {
+        reinit();
+    }
+}
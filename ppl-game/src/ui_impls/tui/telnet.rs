@@ -0,0 +1,207 @@
+//! Telnet transport
+//!
+//! [`Telnet`] wraps a connection to telnet client. It asks client for character mode
+//! (server echoes, nothing is echoed locally) and for window size (NAWS, RFC 1073), and
+//! strips telnet commands from input. Window size reports become `ESC [ 8 ; lines ; cols t`
+//! sequences, so [`InputReader`](super::input::InputReader) gives them as
+//! [`InputEvent::Resize`](super::input::InputEvent::Resize).
+//!
+//! Output needs no changes: UTF-8 never has `IAC` (`0xFF`) byte and screen moves cursor by
+//! escape sequences, not newlines.
+//!
+//! # Example
+//! ```
+//! use std::{io::Write, net::{TcpListener, TcpStream}};
+//! use ppl_game::{
+//!     ui::Point,
+//!     ui_impls::tui::{input::{InputEvent, InputReader, Key, KeyEvent}, telnet::Telnet},
+//! };
+//!
+//! let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+//! let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+//! let (server, _) = listener.accept().unwrap();
+//!
+//! let mut telnet = Telnet::new(server);
+//! telnet.negotiate().unwrap();
+//!
+//! // client agrees, tells its size (100x30) and presses `w` and enter
+//! client.write_all(&[255, 253, 1, 255, 251, 31]).unwrap();
+//! client.write_all(&[255, 250, 31, 0, 100, 0, 30, 255, 240]).unwrap();
+//! client.write_all(b"w\r\0").unwrap();
+//!
+//! let mut input = InputReader::new(telnet);
+//! let key = |key| Some(InputEvent::Key(KeyEvent::new(key)));
+//! assert_eq!(input.read_event().unwrap(), Some(InputEvent::Resize(Point(100, 30))));
+//! assert_eq!(input.read_event().unwrap(), key(Key::Char('w')));
+//! assert_eq!(input.read_event().unwrap(), key(Key::Enter));
+//! ```
+
+use std::{
+    io::{self, Read, Write},
+    os::fd::{AsRawFd, RawFd},
+};
+
+/// Interpret as command
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+/// Subnegotiation begin
+const SB: u8 = 250;
+/// Subnegotiation end
+const SE: u8 = 240;
+
+/// Server echoes input
+const OPT_ECHO: u8 = 1;
+/// Suppress go ahead, with [`OPT_ECHO`] it's character mode
+const OPT_SGA: u8 = 3;
+/// Negotiate about window size
+const OPT_NAWS: u8 = 31;
+
+/// Subnegotiation longer than this is dropped
+const MAX_SUBNEGOTIATION: usize = 32;
+
+/// Where in telnet stream parser is
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum State {
+    Data,
+    /// After `\r`: `\0` or `\n` that follows it is dropped
+    Cr,
+    /// After [`IAC`]
+    Iac,
+    /// After [`IAC`] and one of [`WILL`], [`WONT`], [`DO`], [`DONT`]
+    Option(u8),
+    /// Inside subnegotiation
+    Sb,
+    /// After [`IAC`] inside subnegotiation
+    SbIac,
+}
+
+/// Connection to telnet client, see [module docs](self)
+pub struct Telnet<S> {
+    inner: S,
+    state: State,
+    /// Bytes of current subnegotiation
+    sb: Vec<u8>,
+}
+
+impl<S: Read + Write> Telnet<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            state: State::Data,
+            sb: Vec::new(),
+        }
+    }
+
+    /// Asks client for character mode and window size
+    pub fn negotiate(&mut self) -> io::Result<()> {
+        self.inner.write_all(&[
+            IAC, WILL, OPT_ECHO, IAC, WILL, OPT_SGA, IAC, DO, OPT_SGA, IAC, DO, OPT_NAWS,
+        ])?;
+        self.inner.flush()
+    }
+
+    /// Connection to client
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Turns telnet stream into data, writes it to `out`. Answers client's requests.
+    fn decode(&mut self, input: &[u8], out: &mut Vec<u8>) -> io::Result<()> {
+        let mut replies = Vec::new();
+        for &byte in input {
+            self.state = match (self.state, byte) {
+                (State::Data | State::Cr, IAC) => State::Iac,
+                (State::Cr, b'\0' | b'\n') => State::Data,
+                (State::Data | State::Cr, b'\r') => {
+                    out.push(byte);
+                    State::Cr
+                }
+                (State::Data | State::Cr, _) => {
+                    out.push(byte);
+                    State::Data
+                }
+
+                (State::Iac, IAC) => {
+                    out.push(IAC);
+                    State::Data
+                }
+                (State::Iac, WILL | WONT | DO | DONT) => State::Option(byte),
+                (State::Iac, SB) => {
+                    self.sb.clear();
+                    State::Sb
+                }
+                // NOP, go ahead, break etc. mean nothing here
+                (State::Iac, _) => State::Data,
+
+                (State::Option(command), option) => {
+                    // agree only to options server asked for, refuse others
+                    match (command, option) {
+                        (WILL, OPT_NAWS | OPT_SGA) | (DO, OPT_ECHO | OPT_SGA) => {}
+                        (WILL, _) => replies.extend_from_slice(&[IAC, DONT, option]),
+                        (DO, _) => replies.extend_from_slice(&[IAC, WONT, option]),
+                        _ => {}
+                    }
+                    State::Data
+                }
+
+                (State::Sb, IAC) => State::SbIac,
+                (State::Sb, _) | (State::SbIac, IAC) => {
+                    if self.sb.len() < MAX_SUBNEGOTIATION {
+                        self.sb.push(byte);
+                    }
+                    State::Sb
+                }
+                (State::SbIac, SE) => {
+                    if let [OPT_NAWS, w0, w1, h0, h1] = self.sb[..] {
+                        let cols = u16::from_be_bytes([w0, w1]);
+                        let lines = u16::from_be_bytes([h0, h1]);
+                        // zero is "unknown"
+                        if cols != 0 && lines != 0 {
+                            _ = write!(out, "\x1b[8;{lines};{cols}t");
+                        }
+                    }
+                    State::Data
+                }
+                // broken subnegotiation
+                (State::SbIac, _) => State::Data,
+            };
+        }
+        if !replies.is_empty() {
+            self.inner.write_all(&replies)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: Read + Write> Read for Telnet<S> {
+    /// Reads data without telnet commands. If only commands came, fails with
+    /// [`io::ErrorKind::Interrupted`]: nothing was read, but connection is not closed.
+    ///
+    /// `buf` must be at least 32 bytes, so window size report fits.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // report of window size is less than twice longer than its subnegotiation
+        let raw = &mut [0; 4096];
+        let raw = &mut raw[..(buf.len() / 2).clamp(1, 4096)];
+        let n = self.inner.read(raw)?;
+        if n == 0 {
+            return Ok(0);
+        }
+        let mut out = Vec::with_capacity(n);
+        self.decode(&raw[..n], &mut out)?;
+        if out.is_empty() {
+            return Err(io::ErrorKind::Interrupted.into());
+        }
+        let n = out.len().min(buf.len());
+        buf[..n].copy_from_slice(&out[..n]);
+        Ok(n)
+    }
+}
+
+impl<S: AsRawFd> AsRawFd for Telnet<S> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
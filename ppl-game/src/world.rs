@@ -0,0 +1,381 @@
+//! # Shared world
+//!
+//! [`World`] is a game of several players on the same maps. Every player has own interface,
+//! stats, inventory, position and map, sees other players as
+//! [`BlockTy::OtherPlayer`](crate::ui::BlockTy::OtherPlayer) and can't walk into them.
+//! Players change the world one at a time: server keeps it behind a lock and passes their
+//! inputs to [`World::do_input`]. After every change screens of other players on the same
+//! map are redrawn, so all interfaces are owned by the world.
+//!
+//! Shared world is not saved and not recorded.
+//!
+//! # Example
+//! ```
+//! use ppl_game::{
+//!     clock::TickMode,
+//!     game::GameAction,
+//!     replay::Input,
+//!     ui::{BlockTy, Point},
+//!     ui_impls::headless,
+//!     world::World,
+//! };
+//!
+//! let mut world = World::new_with_seed(42, TickMode::TurnBased);
+//! let alice = world.join(headless::Context::new()).unwrap();
+//! let bob = world.join(headless::Context::new()).unwrap();
+//! assert_eq!(world.player(alice).unwrap().pos, Point(0, 0));
+//! assert_eq!(world.player(bob).unwrap().pos, Point(1, 0));
+//!
+//! // alice can't walk into bob, but sees him moving
+//! world.do_input(alice, Input::Action(GameAction::MoveRight)).unwrap();
+//! assert_eq!(world.player(alice).unwrap().pos, Point(0, 0));
+//! world.do_input(bob, Input::Action(GameAction::MoveRight)).unwrap();
+//! let ui = &world.player(alice).unwrap().handle.ui;
+//! assert_eq!(ui.block_at(Point(2, 0)), BlockTy::OtherPlayer);
+//! assert_eq!(ui.block_at(Point(1, 0)), BlockTy::Air);
+//!
+//! // players who take the same door don't stand on each other
+//! world.do_input(alice, Input::Action(GameAction::MoveDown)).unwrap();
+//! for act in [GameAction::MoveLeft, GameAction::MoveLeft, GameAction::MoveDown] {
+//!     world.do_input(bob, Input::Action(act)).unwrap();
+//! }
+//! assert_eq!(world.player(alice).unwrap().pos, Point(28, 5));
+//! assert_eq!(world.player(bob).unwrap().pos, Point(28, 4));
+//!
+//! // new player takes place of one who left, but id of the left one stays dead
+//! world.leave(bob).unwrap();
+//! let carol = world.join(headless::Context::new()).unwrap();
+//! assert!(world.player(bob).is_none() && world.leave(bob).is_none());
+//! assert!(world.player(carol).is_some());
+//! ```
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use rand::{thread_rng, Rng};
+
+use crate::{
+    assets::maps::GameMap,
+    clock::{Clock, TickMode},
+    game::{GameHandle, Turn},
+    map::GameMaps,
+    player::PlayerInventory,
+    replay::Input,
+    ui::{Context, Fragment, Point},
+};
+
+/// Player in [`World`]. Place of player who left is taken by the next one, but ids are
+/// never reused: session that still holds id of left player can't control the new one.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PlayerId {
+    /// Place in the world
+    index: usize,
+    /// Number of join, unique in the world
+    serial: u64,
+}
+
+/// Why player can't join [`World`]
+pub enum JoinError<UI: Context> {
+    /// There is no free place on the farm. Interface is given back.
+    Full(UI),
+    /// Interface of player failed
+    Ui(UI::Error),
+}
+
+impl<UI: Context> fmt::Debug for JoinError<UI> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full(_) => write!(f, "Full"),
+            Self::Ui(e) => f.debug_tuple("Ui").field(e).finish(),
+        }
+    }
+}
+
+impl<UI: Context> fmt::Display for JoinError<UI> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full(_) => write!(f, "no free place in the world"),
+            Self::Ui(e) => e.fmt(f),
+        }
+    }
+}
+
+/// Everything of one player in shared world
+pub struct WorldPlayer<UI: Context> {
+    pub handle: GameHandle<UI>,
+    pub pos: Point,
+    /// Map player is on
+    pub map: GameMap,
+    pub inventory: PlayerInventory,
+}
+
+/// Game of several players, see [module docs](self)
+///
+/// Player whose interface fails while other player changes the world is removed from it:
+/// [`World::player`] of that player is `None` after that, so its session can end.
+pub struct World<UI: Context> {
+    /// Seed of the world. Each player has own random number generator seeded by it and
+    /// number of player's join.
+    pub seed: u64,
+    pub maps: GameMaps,
+    pub tick_mode: TickMode,
+    clock: Option<Clock>,
+    /// Players with serials of their ids
+    players: Vec<Option<(u64, WorldPlayer<UI>)>>,
+    /// Number of joins, serial of next [`PlayerId`]
+    joins: u64,
+}
+
+impl<UI: Context> World<UI> {
+    /// Creates world without players with random seed
+    pub fn new(tick_mode: TickMode) -> Self {
+        Self::new_with_seed(thread_rng().gen(), tick_mode)
+    }
+
+    /// Creates world without players. Worlds with same seed and same inputs are same.
+    pub fn new_with_seed(seed: u64, tick_mode: TickMode) -> Self {
+        let clock = match tick_mode {
+            TickMode::RealTime(interval) => Some(Clock::new(interval, Instant::now())),
+            TickMode::TurnBased => None,
+        };
+        Self {
+            seed,
+            maps: GameMaps::init(),
+            tick_mode,
+            clock,
+            players: Vec::new(),
+            joins: 0,
+        }
+    }
+
+    /// Player by id, `None` if player left
+    pub fn player(&self, id: PlayerId) -> Option<&WorldPlayer<UI>> {
+        match self.players.get(id.index)? {
+            Some((serial, player)) if *serial == id.serial => Some(player),
+            _ => None,
+        }
+    }
+    /// Player by id, `None` if player left
+    pub fn player_mut(&mut self, id: PlayerId) -> Option<&mut WorldPlayer<UI>> {
+        match self.players.get_mut(id.index)? {
+            Some((serial, player)) if *serial == id.serial => Some(player),
+            _ => None,
+        }
+    }
+
+    /// Ids of all players
+    fn ids(&self) -> Vec<PlayerId> {
+        self.players
+            .iter()
+            .enumerate()
+            .filter_map(|(index, p)| p.as_ref().map(|(serial, _)| (index, *serial)))
+            .map(|(index, serial)| PlayerId { index, serial })
+            .collect()
+    }
+
+    /// Number of players
+    pub fn len(&self) -> usize {
+        self.players.iter().flatten().count()
+    }
+
+    /// Are there no players
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds player with interface `ui` to the farm. Player appears at the first free
+    /// place from top left corner. Screens are drawn and applied. Fails with
+    /// [`JoinError::Full`] if every place is taken.
+    ///
+    /// ```
+    /// use ppl_game::{
+    ///     clock::TickMode,
+    ///     map::MapData,
+    ///     ui::Point,
+    ///     ui_impls::headless,
+    ///     world::{JoinError, World},
+    /// };
+    ///
+    /// let mut world = World::new_with_seed(42, TickMode::TurnBased);
+    /// world.maps.farm = MapData {
+    ///     size: Point(1, 1),
+    ///     ..Default::default()
+    /// };
+    /// assert!(world.join(headless::Context::new()).is_ok());
+    /// let full = world.join(headless::Context::new());
+    /// assert!(matches!(full, Err(JoinError::Full(_))));
+    /// assert_eq!(world.len(), 1);
+    /// ```
+    pub fn join(&mut self, ui: UI) -> Result<PlayerId, JoinError<UI>> {
+        self.maps.switch_to(GameMap::Farm);
+        let Some(pos) = self.spawn_point() else {
+            return Err(JoinError::Full(ui));
+        };
+        let index = match self.players.iter().position(Option::is_none) {
+            Some(index) => index,
+            None => {
+                self.players.push(None);
+                self.players.len() - 1
+            }
+        };
+        let id = PlayerId {
+            index,
+            serial: self.joins,
+        };
+        self.joins += 1;
+        let player = WorldPlayer {
+            handle: GameHandle::new(ui, self.seed.wrapping_add(id.serial)),
+            pos,
+            map: GameMap::Farm,
+            inventory: Default::default(),
+        };
+        self.players[index] = Some((id.serial, player));
+        self.redraw_screen(id).map_err(JoinError::Ui)?;
+        self.redraw_others(Some(id));
+        Ok(id)
+    }
+
+    /// Removes player from the world and gives its interface back
+    pub fn leave(&mut self, id: PlayerId) -> Option<WorldPlayer<UI>> {
+        self.player(id)?;
+        let (_, player) = self.players[id.index].take()?;
+        self.redraw_others(Some(id));
+        Some(player)
+    }
+
+    /// Free place on farm for new player, farm must be current map
+    fn spawn_point(&self) -> Option<Point> {
+        let taken: Vec<Point> = self
+            .others(None)
+            .into_iter()
+            .filter(|(map, _)| *map == GameMap::Farm)
+            .map(|(_, pos)| pos)
+            .collect();
+        self.maps.free_near(Point(0, 0), &taken)
+    }
+
+    /// Maps and positions of players except `except`
+    fn others(&self, except: Option<PlayerId>) -> Vec<(GameMap, Point)> {
+        let except = except.map(|id| id.serial);
+        self.players
+            .iter()
+            .flatten()
+            .filter(|(serial, _)| Some(*serial) != except)
+            .map(|(_, p)| (p.map, p.pos))
+            .collect()
+    }
+
+    /// Runs `f` with player `id` and the world, player's map is current
+    fn with_turn<R>(&mut self, id: PlayerId, f: impl FnOnce(&mut Turn<'_, UI>) -> R) -> Option<R> {
+        let map = self.player(id)?.map;
+        let others = self.others(Some(id));
+        self.maps.switch_to(map);
+        let (_, player) = self.players[id.index].as_mut()?;
+        let mut turn = Turn {
+            handle: &mut player.handle,
+            pos: &mut player.pos,
+            inventory: &mut player.inventory,
+            maps: &mut self.maps,
+            others: &others,
+            tick_mode: self.tick_mode,
+        };
+        let result = f(&mut turn);
+        player.map = self.maps.current_map;
+        Some(result)
+    }
+
+    /// Do [`Input`] of player `id` and redraw screens of players who see changes. Changes
+    /// are applied. [`Input::Tick`] is a tick of whole world, see [`World::do_due_ticks`].
+    pub fn do_input(&mut self, id: PlayerId, input: Input) -> Result<(), UI::Error> {
+        if input == Input::Tick {
+            self.do_timer_tick();
+            return Ok(());
+        }
+        let Some(result) = self.with_turn(id, |turn| {
+            turn.do_input(input)?;
+            turn.handle.ui.apply()
+        }) else {
+            return Ok(());
+        };
+        self.redraw_others(Some(id));
+        result
+    }
+
+    /// Clears and redraws all fragments of player `id`, e.g. after its interface was
+    /// resized. Changes are applied.
+    pub fn redraw_screen(&mut self, id: PlayerId) -> Result<(), UI::Error> {
+        self.with_turn(id, |turn| {
+            turn.redraw_screen()?;
+            turn.handle.ui.apply()
+        })
+        .unwrap_or(Ok(()))
+    }
+
+    /// Redraws maps of all players except `except`. Players whose interfaces fail are
+    /// removed, the rest are redrawn again without them.
+    fn redraw_others(&mut self, except: Option<PlayerId>) {
+        loop {
+            let mut failed = Vec::new();
+            for other in self.ids() {
+                if Some(other) == except {
+                    continue;
+                }
+                let result = self.with_turn(other, |turn| {
+                    turn.handle.ui.main().clear()?;
+                    turn.redraw_all()?;
+                    turn.update_status_if_needed()?;
+                    turn.handle.ui.apply()
+                });
+                if let Some(Err(_)) = result {
+                    failed.push(other);
+                }
+            }
+            if failed.is_empty() {
+                return;
+            }
+            for other in failed {
+                self.players[other.index] = None;
+            }
+        }
+    }
+
+    /// Time until next tick in real-time mode, `None` if world is turn-based
+    pub fn until_next_tick(&self, now: Instant) -> Option<Duration> {
+        self.clock.as_ref().map(|c| c.until_next(now))
+    }
+
+    /// Does ticks that are due at `now` in real-time mode (see [`Clock::due`]). Every
+    /// player can call it, ticks are done once.
+    pub fn do_due_ticks(&mut self, now: Instant) {
+        let ticks = self.clock.as_mut().map_or(0, |c| c.due(now));
+        for _ in 0..ticks {
+            self.do_timer_tick();
+        }
+    }
+
+    /// Random tick of maps with players and of all players, screens are redrawn
+    fn do_timer_tick(&mut self) {
+        let mut ticked = Vec::new();
+        for id in self.ids() {
+            let Some(map) = self.player(id).map(|p| p.map) else {
+                continue;
+            };
+            let tick_map = !ticked.contains(&map);
+            ticked.push(map);
+            self.with_turn(id, |turn| {
+                let mut tick = || {
+                    if tick_map {
+                        turn.maps.do_random_tick(turn.handle, turn.inventory)?;
+                    }
+                    turn.do_player_tick()?;
+                    turn.draw_lore()?;
+                    turn.draw_player_info()
+                };
+                _ = tick();
+            });
+        }
+        self.redraw_others(None);
+    }
+}
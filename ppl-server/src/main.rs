@@ -0,0 +1,404 @@
+//! Telnet server: every connection plays its own game, or all of them play in one
+//! [`World`] with `--shared`
+//!
+//! Each session runs in its own thread, their number is limited by `--max-sessions`.
+//! Shared world is kept behind a lock, so players change it one at a time. Session ends
+//! after [`IDLE_TIMEOUT`] without input.
+//!
+//! Build it with `cargo build --profile server -p ppl-server`: release profile aborts on
+//! panic, so panic of one session would stop all of them.
+
+use ppl_game::{
+    assets::registry::Registry,
+    clock::{Clock, TickMode},
+    game::{Game, GameHandle},
+    replay::Input,
+    ui::{Color, Context as _},
+    ui_impls::tui::{
+        self,
+        input::{InputEvent, InputReader, Poll},
+        palette::{ColorDepth, Palette},
+        telnet::Telnet,
+        DEFAULT_SIZE,
+    },
+    world::{JoinError, PlayerId, World},
+};
+use ppl_tui::{
+    keymap::{Command, Keymap},
+    redraw_screen, show_help, status_message,
+};
+use std::{
+    io::{self, Write},
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+/// Address to listen on if `--listen` is not set
+const DEFAULT_LISTEN: &str = "0.0.0.0:2323";
+/// Sessions at once if `--max-sessions` is not set
+const DEFAULT_MAX_SESSIONS: usize = 8;
+/// Session without input for this long is ended
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// Client that doesn't take output for this long is disconnected. Screens of shared world
+/// are written under its lock, so one stalled client stops others no longer than this.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Command line arguments
+struct Args {
+    /// `--listen <addr>`
+    listen: String,
+    /// `--max-sessions <n>`
+    max_sessions: usize,
+    /// `--keymap <preset or path>`, see [`Keymap::load`]
+    keymap: Option<String>,
+    /// `--tick <ms>`, real-time mode with tick interval
+    tick: Option<Duration>,
+    /// `--things <path>`, more block and item definitions, see [`Registry::load`]
+    things: Option<PathBuf>,
+    /// `--shared`, all sessions play in one world
+    shared: bool,
+}
+
+impl Args {
+    fn parse() -> Result<Self, String> {
+        let mut args = Self {
+            listen: DEFAULT_LISTEN.to_owned(),
+            max_sessions: DEFAULT_MAX_SESSIONS,
+            keymap: None,
+            tick: None,
+            things: None,
+            shared: false,
+        };
+        let mut iter = std::env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            let mut value = || iter.next().ok_or(format!("{arg} requires a value"));
+            match arg.as_str() {
+                "--listen" => args.listen = value()?,
+                "--max-sessions" => {
+                    let value = value()?;
+                    args.max_sessions = value
+                        .parse()
+                        .ok()
+                        .filter(|v: &usize| *v > 0)
+                        .ok_or(format!("invalid number of sessions `{value}`"))?;
+                }
+                "--keymap" => args.keymap = Some(value()?),
+                "--things" => args.things = Some(value()?.into()),
+                "--tick" => {
+                    let value = value()?;
+                    let ms = value
+                        .parse()
+                        .ok()
+                        .filter(|v: &u64| *v > 0)
+                        .ok_or(format!("invalid tick interval `{value}`"))?;
+                    args.tick = Some(Duration::from_millis(ms));
+                }
+                "--shared" => args.shared = true,
+                _ => return Err(format!("unknown argument `{arg}`")),
+            }
+        }
+        Ok(args)
+    }
+}
+
+/// Interface of session
+type Ui = tui::Context<TcpStream>;
+
+/// Settings of all sessions
+struct Config {
+    keymap: Keymap,
+    tick_mode: TickMode,
+    /// World of all sessions if server is started with `--shared`
+    world: Option<Mutex<World<Ui>>>,
+}
+
+/// Place of session in limit, freed on drop
+struct Slot(Arc<AtomicUsize>);
+
+impl Slot {
+    /// Takes place if there are less than `max` sessions
+    fn take(active: &Arc<AtomicUsize>, max: usize) -> Option<Self> {
+        active
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n < max).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| Self(active.clone()))
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Time of last input, session is ended when it's too old
+struct Idle(Instant);
+
+impl Idle {
+    fn new() -> Self {
+        Self(Instant::now())
+    }
+
+    /// Poll timeout: until next tick, if any, but not after idle timeout
+    fn timeout(&self, until_tick: Option<Duration>) -> Duration {
+        let left = IDLE_TIMEOUT.saturating_sub(self.0.elapsed());
+        until_tick.map_or(left, |t| t.min(left))
+    }
+
+    /// Fails if session is idle for too long
+    fn check(&self) -> io::Result<()> {
+        if self.0.elapsed() >= IDLE_TIMEOUT {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "idle for too long"));
+        }
+        Ok(())
+    }
+}
+
+/// Plays one game over connection until player quits or disconnects
+fn session(stream: TcpStream, config: &Config) -> io::Result<()> {
+    stream.set_nodelay(true)?;
+    stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let mut telnet = Telnet::new(stream.try_clone()?);
+    telnet.negotiate()?;
+    let mut input = InputReader::new(telnet);
+
+    // real size comes with first window size report
+    let palette = Palette::new(ColorDepth::Ansi16);
+    let mut ui = tui::Context::with_output(stream, DEFAULT_SIZE, palette);
+    ui.enter_screen()?;
+    if let Some(world) = &config.world {
+        return play_shared(world, ui, &mut input, &config.keymap);
+    }
+    let mut game = Game::new(ui);
+    game.tick_mode = config.tick_mode;
+    let result = play(&mut game, &mut input, config);
+    // connection may be closed already
+    _ = game.handle.ui.leave_screen();
+    result
+}
+
+/// Game loop of session, like one of `ppl-tui` without saves and recording
+fn play(
+    game: &mut Game<Ui>,
+    input: &mut InputReader<Telnet<TcpStream>>,
+    config: &Config,
+) -> io::Result<()> {
+    game.redraw_all()?;
+    game.draw_player_info()?;
+    game.handle.ui.apply()?;
+
+    let keymap = &config.keymap;
+    let mut clock = match game.tick_mode {
+        TickMode::RealTime(interval) => Some(Clock::new(interval, Instant::now())),
+        TickMode::TurnBased => None,
+    };
+    let mut help_shown = false;
+    let mut idle = Idle::new();
+    loop {
+        idle.check()?;
+        if let Some(clock) = &mut clock {
+            let ticks = clock.due(Instant::now());
+            for _ in 0..ticks {
+                game.do_input(Input::Tick)?;
+            }
+            if ticks != 0 {
+                if help_shown {
+                    show_help(&mut game.handle, keymap)?;
+                }
+                game.handle.ui.apply()?;
+            }
+        }
+
+        let timeout = idle.timeout(clock.as_ref().map(|c| c.until_next(Instant::now())));
+        let key = match input.poll_event(Some(timeout))? {
+            Poll::Event(InputEvent::Key(key)) => key,
+            Poll::Event(InputEvent::Resize(size)) => {
+                game.handle.ui.resize(size);
+                redraw_screen(game, keymap, help_shown)?;
+                continue;
+            }
+            Poll::Event(InputEvent::Mouse(_)) | Poll::Timeout | Poll::Interrupted => continue,
+            Poll::Closed => return Ok(()),
+        };
+        idle = Idle::new();
+        let command = keymap.get(key);
+        help_shown = command == Some(Command::Help);
+        match command {
+            Some(Command::Input(input)) => game.do_input(input)?,
+            Some(Command::Save | Command::Load) => {
+                status_message(&mut game.handle, Color::Health, "no saves on server")?
+            }
+            Some(Command::Help) => show_help(&mut game.handle, keymap)?,
+            Some(Command::Quit) => return Ok(()),
+            None => continue,
+        }
+
+        game.handle.ui.apply()?;
+    }
+}
+
+/// Locks shared world. Panic of other session doesn't stop the others.
+fn lock(world: &Mutex<World<Ui>>) -> MutexGuard<'_, World<Ui>> {
+    world.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Handle of player `id`. Fails if player is not in the world anymore.
+fn handle(world: &mut World<Ui>, id: PlayerId) -> io::Result<&mut GameHandle<Ui>> {
+    world
+        .player_mut(id)
+        .map(|p| &mut p.handle)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "player left the world"))
+}
+
+/// Player in shared world, leaves it on drop, even if session panics
+struct Joined<'a> {
+    world: &'a Mutex<World<Ui>>,
+    id: PlayerId,
+}
+
+impl Drop for Joined<'_> {
+    fn drop(&mut self) {
+        if let Some(mut player) = lock(self.world).leave(self.id) {
+            _ = player.handle.ui.leave_screen();
+        }
+    }
+}
+
+/// Joins shared world, plays until player quits or disconnects and leaves it
+fn play_shared(
+    world: &Mutex<World<Ui>>,
+    ui: Ui,
+    input: &mut InputReader<Telnet<TcpStream>>,
+    keymap: &Keymap,
+) -> io::Result<()> {
+    let id = match lock(world).join(ui) {
+        Ok(id) => id,
+        Err(JoinError::Full(mut ui)) => {
+            ui.leave_screen()?;
+            ui.output_mut()
+                .write_all(b"world is full, try again later\r\n")?;
+            return Err(io::Error::other("world is full"));
+        }
+        Err(JoinError::Ui(e)) => return Err(e),
+    };
+    let _joined = Joined { world, id };
+    play_in_world(world, id, input, keymap)
+}
+
+/// Game loop of player `id` in shared world. World is locked only while it's changed.
+fn play_in_world(
+    world: &Mutex<World<Ui>>,
+    id: PlayerId,
+    input: &mut InputReader<Telnet<TcpStream>>,
+    keymap: &Keymap,
+) -> io::Result<()> {
+    let mut help_shown = false;
+    let mut idle = Idle::new();
+    loop {
+        idle.check()?;
+        // any session does due ticks of the world
+        let timeout = {
+            let mut world = lock(world);
+            // player is removed if other session fails to write to it
+            handle(&mut world, id)?;
+            let now = Instant::now();
+            world.do_due_ticks(now);
+            idle.timeout(world.until_next_tick(now))
+        };
+
+        let key = match input.poll_event(Some(timeout))? {
+            Poll::Event(InputEvent::Key(key)) => key,
+            Poll::Event(InputEvent::Resize(size)) => {
+                let mut world = lock(world);
+                handle(&mut world, id)?.ui.resize(size);
+                world.redraw_screen(id)?;
+                if help_shown {
+                    let handle = handle(&mut world, id)?;
+                    show_help(handle, keymap)?;
+                    handle.ui.apply()?;
+                }
+                continue;
+            }
+            Poll::Event(InputEvent::Mouse(_)) | Poll::Timeout | Poll::Interrupted => continue,
+            Poll::Closed => return Ok(()),
+        };
+        idle = Idle::new();
+        let command = keymap.get(key);
+        help_shown = command == Some(Command::Help);
+        let mut world = lock(world);
+        match command {
+            Some(Command::Input(input)) => world.do_input(id, input)?,
+            Some(Command::Save | Command::Load) => {
+                let handle = handle(&mut world, id)?;
+                status_message(handle, Color::Health, "no saves on server")?;
+                handle.ui.apply()?;
+            }
+            Some(Command::Help) => {
+                let handle = handle(&mut world, id)?;
+                show_help(handle, keymap)?;
+                handle.ui.apply()?;
+            }
+            Some(Command::Quit) => return Ok(()),
+            None => continue,
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse()?;
+    if let Some(path) = &args.things {
+        Registry::load(path)?
+            .install()
+            .map_err(|_| "definitions are already in use")?;
+    }
+    let tick_mode = args.tick.map_or(TickMode::TurnBased, TickMode::RealTime);
+    let config = Arc::new(Config {
+        keymap: Keymap::load(args.keymap.as_deref())?,
+        tick_mode,
+        world: args.shared.then(|| Mutex::new(World::new(tick_mode))),
+    });
+    let listener = TcpListener::bind(&args.listen)?;
+    eprintln!("listening on {}", listener.local_addr()?);
+
+    let active = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("can't accept connection: {e}");
+                continue;
+            }
+        };
+        let peer = stream
+            .peer_addr()
+            .map_or_else(|_| "unknown".to_owned(), |a| a.to_string());
+        let Some(slot) = Slot::take(&active, args.max_sessions) else {
+            eprintln!("{peer}: refused, {} sessions already", args.max_sessions);
+            _ = stream.write_all(b"server is full, try again later\r\n");
+            continue;
+        };
+        let config = config.clone();
+        let spawned = std::thread::Builder::new()
+            .name(format!("session {peer}"))
+            .spawn(move || {
+                let _slot = slot;
+                eprintln!("{peer}: connected");
+                match session(stream, &config) {
+                    Ok(()) => eprintln!("{peer}: disconnected"),
+                    Err(e) => eprintln!("{peer}: {e}"),
+                }
+            });
+        if let Err(e) = spawned {
+            eprintln!("can't start session: {e}");
+        }
+    }
+    Ok(())
+}
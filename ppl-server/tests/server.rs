@@ -0,0 +1,246 @@
+//! Runs `ppl-server` and plays it over loopback connections
+
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpStream},
+    process::{Child, Command, Stdio},
+    time::{Duration, Instant},
+};
+
+/// How long client waits for output that must come
+const WAIT: Duration = Duration::from_secs(10);
+/// Client that gets nothing for this long has whole screen
+const QUIET: Duration = Duration::from_millis(300);
+
+/// Line of terminal where map starts: after status and border
+const MAP_TOP: usize = 3;
+
+/// Server process, killed on drop
+struct Server {
+    child: Child,
+    addr: SocketAddr,
+}
+
+impl Server {
+    /// Starts server on free loopback port with `args`
+    fn start(args: &[&str]) -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_ppl-server"))
+            .args(["--listen", "127.0.0.1:0", "--keymap", "wasd"])
+            .args(args)
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("server starts");
+        let mut stderr = BufReader::new(child.stderr.take().unwrap());
+        let mut line = String::new();
+        stderr.read_line(&mut line).unwrap();
+        let addr = line
+            .trim()
+            .strip_prefix("listening on ")
+            .and_then(|a| a.parse().ok())
+            .unwrap_or_else(|| panic!("unexpected server output `{line}`"));
+        // server must not block on full pipe
+        std::thread::spawn(move || io::copy(&mut stderr, &mut io::sink()));
+        Self { child, addr }
+    }
+
+    fn connect(&self) -> Client {
+        let stream = TcpStream::connect(self.addr).unwrap();
+        stream.set_read_timeout(Some(QUIET)).unwrap();
+        Client(stream)
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        _ = self.child.kill();
+        _ = self.child.wait();
+    }
+}
+
+/// Telnet client
+struct Client(TcpStream);
+
+impl Client {
+    /// Reads until server is quiet or closes connection
+    fn read_quiet(&mut self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let buf = &mut [0; 4096];
+        loop {
+            match self.0.read(buf) {
+                Ok(0) => return out,
+                Ok(n) => out.extend_from_slice(&buf[..n]),
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    return out
+                }
+                Err(e) => panic!("{e}"),
+            }
+        }
+    }
+
+    /// Reads whole screen: waits for first output, then until server is quiet
+    fn read_screen(&mut self) -> Vec<u8> {
+        let start = Instant::now();
+        loop {
+            let out = self.read_quiet();
+            if !out.is_empty() {
+                return [out, self.read_quiet()].concat();
+            }
+            assert!(start.elapsed() < WAIT, "no output from server");
+        }
+    }
+
+    /// Reads until server closes connection
+    fn read_to_end(&mut self) -> Vec<u8> {
+        self.0.set_read_timeout(Some(WAIT)).unwrap();
+        let mut out = Vec::new();
+        self.0.read_to_end(&mut out).unwrap();
+        out
+    }
+
+    fn send(&mut self, keys: &[u8]) {
+        self.0.write_all(keys).unwrap();
+    }
+}
+
+/// Screen of 80x24 terminal. Knows only cursor moves and clearing, styles are skipped.
+struct Term {
+    lines: Vec<Vec<char>>,
+    cursor: (usize, usize),
+}
+
+impl Term {
+    fn new() -> Self {
+        Self {
+            lines: vec![vec![' '; 80]; 24],
+            cursor: (0, 0),
+        }
+    }
+
+    /// Applies output of server
+    fn feed(&mut self, out: &[u8]) {
+        // server sends only 3 byte telnet commands: IAC, command, option
+        let mut data = Vec::new();
+        let mut bytes = out.iter();
+        while let Some(&b) = bytes.next() {
+            if b == 0xFF {
+                bytes.nth(1);
+            } else {
+                data.push(b);
+            }
+        }
+        let text = String::from_utf8_lossy(&data);
+        let mut chars = text.chars();
+        while let Some(ch) = chars.next() {
+            if ch != '\x1b' {
+                let (x, y) = self.cursor;
+                if let Some(cell) = self.lines.get_mut(y).and_then(|l| l.get_mut(x)) {
+                    *cell = ch;
+                }
+                self.cursor.0 += 1;
+                continue;
+            }
+            if chars.next() != Some('[') {
+                continue;
+            }
+            let mut params = String::new();
+            let Some(end) = chars.find(|&c| {
+                let end = ('@'..='~').contains(&c);
+                if !end {
+                    params.push(c);
+                }
+                end
+            }) else {
+                return;
+            };
+            match end {
+                'H' => {
+                    let mut at = params.split(';').map(|p| p.parse().unwrap_or(1));
+                    let y: usize = at.next().unwrap_or(1);
+                    let x: usize = at.next().unwrap_or(1);
+                    self.cursor = (x - 1, y - 1);
+                }
+                'J' if params == "2" => self.lines.iter_mut().for_each(|l| l.fill(' ')),
+                _ => {}
+            }
+        }
+    }
+
+    fn at(&self, x: usize, y: usize) -> char {
+        self.lines[y][x]
+    }
+}
+
+#[test]
+fn refuses_sessions_over_limit() {
+    let server = Server::start(&["--max-sessions", "2"]);
+    let mut clients = [server.connect(), server.connect()];
+    for client in &mut clients {
+        assert!(String::from_utf8_lossy(&client.read_screen()).contains("-[ MAP ]-"));
+    }
+
+    let mut extra = server.connect();
+    let out = extra.read_to_end();
+    assert_eq!(
+        String::from_utf8_lossy(&out),
+        "server is full, try again later\r\n"
+    );
+
+    // place is freed when session ends, soon after connection is closed
+    clients[0].send(b"q");
+    clients[0].read_to_end();
+    let start = Instant::now();
+    loop {
+        let out = server.connect().read_screen();
+        if String::from_utf8_lossy(&out).contains("-[ MAP ]-") {
+            break;
+        }
+        assert!(start.elapsed() < WAIT, "place is not freed");
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[test]
+fn sessions_play_own_games() {
+    let server = Server::start(&[]);
+    let mut alice = server.connect();
+    let mut bob = server.connect();
+    alice.read_screen();
+    bob.read_screen();
+
+    // only screen of the one who moves changes
+    alice.send(b"d");
+    assert!(!alice.read_screen().is_empty());
+    assert!(bob.read_quiet().is_empty());
+
+    bob.send(b"s");
+    assert!(!bob.read_screen().is_empty());
+    assert!(alice.read_quiet().is_empty());
+}
+
+#[test]
+fn shared_world_shows_other_players() {
+    let server = Server::start(&["--shared"]);
+    let mut alice = server.connect();
+    let mut screen = Term::new();
+    screen.feed(&alice.read_screen());
+    assert_eq!(screen.at(0, MAP_TOP), '@');
+
+    // bob joins next to alice
+    let mut bob = server.connect();
+    bob.read_screen();
+    screen.feed(&alice.read_screen());
+    assert_eq!(screen.at(1, MAP_TOP), 'P');
+
+    // and alice sees bob moving
+    bob.send(b"d");
+    bob.read_screen();
+    screen.feed(&alice.read_screen());
+    assert_eq!(screen.at(2, MAP_TOP), 'P');
+    assert_ne!(screen.at(1, MAP_TOP), 'P');
+    assert_eq!(screen.at(0, MAP_TOP), '@');
+}
@@ -0,0 +1,292 @@
+//! Key bindings
+//!
+//! Keymap is one of presets (`wasd`, `vi`, `arrows`), optionally changed by config file:
+//!
+//! ```toml
+//! # base preset, `wasd` if not set
+//! preset = "vi"
+//!
+//! [keys]
+//! space = "interact"
+//! f = "interact"
+//! ```
+//!
+//! Keys are single chars or names like `up`, `pageup`, `f1`, `space`, `enter`, `esc` with
+//! optional modifiers: `ctrl+s`, `alt+shift+left`. See [`KeyEvent`] parsing for details
+//! and [`COMMANDS`] for command names.
+//!
+//! Keys of config replace bindings of preset, other keys of preset stay.
+//!
+//! # Example
+//! ```
+//! use ppl_game::{game::GameAction, replay::Input, ui_impls::tui::input::KeyEvent};
+//! use ppl_tui::keymap::{Command, Keymap};
+//!
+//! let key = |name: &str| name.parse::<KeyEvent>().unwrap();
+//! let action = |action| Some(Command::Input(Input::Action(action)));
+//!
+//! let vi = Keymap::load(Some("vi")).unwrap();
+//! assert_eq!(vi.get(key("h")), action(GameAction::MoveLeft));
+//!
+//! let keymap = Keymap::parse(r#"
+//!     preset = "vi"
+//!     [keys]
+//!     h = "quit"
+//!     space = "interact"
+//! "#).unwrap();
+//! assert_eq!(keymap.get(key("h")), Some(Command::Quit));
+//! assert_eq!(keymap.get(key("space")), action(GameAction::Interact));
+//! // rest of preset stays
+//! assert_eq!(keymap.get(key("e")), action(GameAction::Interact));
+//! assert_eq!(keymap.get(key("j")), action(GameAction::MoveDown));
+//!
+//! assert!(Keymap::parse("[keys]\nnokey = \"interact\"").is_err());
+//! assert!(Keymap::parse("[keys]\nf = \"fly\"").is_err());
+//! assert!(Keymap::parse("preset = \"emacs\"").is_err());
+//! ```
+
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use ppl_game::{
+    game::{GameAction, ItemAction},
+    replay::Input,
+    ui_impls::tui::input::KeyEvent,
+};
+use serde::Deserialize;
+
+/// What key does
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Command {
+    Input(Input),
+    Save,
+    Load,
+    Help,
+    Quit,
+}
+
+const fn action(action: GameAction) -> Command {
+    Command::Input(Input::Action(action))
+}
+const fn item(item: ItemAction) -> Command {
+    action(GameAction::Item(item))
+}
+
+/// All commands with names used in config
+pub const COMMANDS: [(&str, Command); 28] = [
+    ("move-up", action(GameAction::MoveUp)),
+    ("move-down", action(GameAction::MoveDown)),
+    ("move-left", action(GameAction::MoveLeft)),
+    ("move-right", action(GameAction::MoveRight)),
+    ("interact", action(GameAction::Interact)),
+    ("inventory", Command::Input(Input::ToggleInventory)),
+    ("items", Command::Input(Input::ToggleItems)),
+    ("choose-1", action(GameAction::Choose(1))),
+    ("choose-2", action(GameAction::Choose(2))),
+    ("choose-3", action(GameAction::Choose(3))),
+    ("choose-4", action(GameAction::Choose(4))),
+    ("choose-5", action(GameAction::Choose(5))),
+    ("choose-6", action(GameAction::Choose(6))),
+    ("choose-7", action(GameAction::Choose(7))),
+    ("choose-8", action(GameAction::Choose(8))),
+    ("choose-9", action(GameAction::Choose(9))),
+    ("prev-page", item(ItemAction::PrevPage)),
+    ("next-page", item(ItemAction::NextPage)),
+    ("cursor-up", item(ItemAction::CursorUp)),
+    ("cursor-down", item(ItemAction::CursorDown)),
+    ("use", item(ItemAction::Use)),
+    ("drop", item(ItemAction::Drop)),
+    ("inspect", item(ItemAction::Inspect)),
+    ("sort", item(ItemAction::Sort)),
+    ("save", Command::Save),
+    ("load", Command::Load),
+    ("help", Command::Help),
+    ("quit", Command::Quit),
+];
+
+fn command(name: &str) -> Option<Command> {
+    COMMANDS.iter().find(|(n, _)| *n == name).map(|(_, c)| *c)
+}
+
+/// Names of presets
+pub const PRESETS: [&str; 3] = ["wasd", "vi", "arrows"];
+
+/// Bindings shared by all presets
+const COMMON: &[(&str, &str)] = &[
+    ("e", "interact"),
+    ("i", "inventory"),
+    ("o", "items"),
+    ("1", "choose-1"),
+    ("2", "choose-2"),
+    ("3", "choose-3"),
+    ("4", "choose-4"),
+    ("5", "choose-5"),
+    ("6", "choose-6"),
+    ("7", "choose-7"),
+    ("8", "choose-8"),
+    ("9", "choose-9"),
+    ("[", "prev-page"),
+    ("]", "next-page"),
+    ("u", "use"),
+    ("x", "drop"),
+    ("v", "inspect"),
+    ("z", "sort"),
+    ("S", "save"),
+    ("L", "load"),
+    ("?", "help"),
+    ("q", "quit"),
+];
+
+fn preset(name: &str) -> Option<&'static [(&'static str, &'static str)]> {
+    Some(match name {
+        "wasd" => &[
+            ("w", "move-up"),
+            ("a", "move-left"),
+            ("s", "move-down"),
+            ("d", "move-right"),
+            ("k", "cursor-up"),
+            ("j", "cursor-down"),
+        ],
+        "vi" => &[
+            ("k", "move-up"),
+            ("h", "move-left"),
+            ("j", "move-down"),
+            ("l", "move-right"),
+            ("K", "cursor-up"),
+            ("J", "cursor-down"),
+        ],
+        "arrows" => &[
+            ("up", "move-up"),
+            ("left", "move-left"),
+            ("down", "move-down"),
+            ("right", "move-right"),
+            ("enter", "interact"),
+            ("k", "cursor-up"),
+            ("j", "cursor-down"),
+        ],
+        _ => return None,
+    })
+}
+
+/// Keymap config file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeysFile {
+    preset: Option<String>,
+    #[serde(default)]
+    keys: BTreeMap<String, String>,
+}
+
+/// Maps keys to commands
+pub struct Keymap {
+    bindings: BTreeMap<KeyEvent, Command>,
+}
+
+impl Keymap {
+    /// Keymap from preset
+    pub fn preset(name: &str) -> Result<Self, String> {
+        let bindings = preset(name).ok_or_else(|| {
+            format!(
+                "unknown keymap `{name}`, expected one of {}",
+                PRESETS.join(", ")
+            )
+        })?;
+        let mut keymap = Self {
+            bindings: BTreeMap::new(),
+        };
+        for (key, cmd) in COMMON.iter().chain(bindings) {
+            keymap.bind(key, cmd).expect("invalid preset");
+        }
+        Ok(keymap)
+    }
+
+    /// Parses keymap config
+    pub fn parse(src: &str) -> Result<Self, String> {
+        let file: KeysFile = toml::from_str(src).map_err(|e| e.to_string())?;
+        let mut keymap = Self::preset(file.preset.as_deref().unwrap_or("wasd"))?;
+        for (key, cmd) in &file.keys {
+            keymap.bind(key, cmd)?;
+        }
+        Ok(keymap)
+    }
+
+    /// Loads keymap by `--keymap` value (preset name or path to config). Without it,
+    /// loads [`Keymap::config_path`] if it exists or `wasd` preset.
+    pub fn load(arg: Option<&str>) -> Result<Self, String> {
+        let path = match arg {
+            Some(name) if preset(name).is_some() => return Self::preset(name),
+            Some(path) => PathBuf::from(path),
+            None => match Self::config_path().filter(|p| p.exists()) {
+                Some(path) => path,
+                None => return Self::preset("wasd"),
+            },
+        };
+        let src = fs::read_to_string(&path).map_err(|e| {
+            let presets = PRESETS.join(", ");
+            format!(
+                "can't read keymap {} ({e}), presets: {presets}",
+                path.display()
+            )
+        })?;
+        Self::parse(&src).map_err(|e| format!("invalid keymap {}: {e}", path.display()))
+    }
+
+    /// `$XDG_CONFIG_HOME/ppl/keys.toml` or `~/.config/ppl/keys.toml`
+    pub fn config_path() -> Option<PathBuf> {
+        let config = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config.join("ppl").join("keys.toml"))
+    }
+
+    fn bind(&mut self, key: &str, cmd: &str) -> Result<(), String> {
+        let key: KeyEvent = key.parse().map_err(|e| format!("{e}"))?;
+        let cmd = command(cmd).ok_or(format!("unknown command `{cmd}`"))?;
+        self.bindings.insert(key, cmd);
+        Ok(())
+    }
+
+    /// Command bound to key
+    pub fn get(&self, key: KeyEvent) -> Option<Command> {
+        self.bindings.get(&key).copied()
+    }
+
+    /// First key bound to command
+    pub fn key_of(&self, cmd: Command) -> Option<KeyEvent> {
+        self.bindings
+            .iter()
+            .find(|(_, c)| **c == cmd)
+            .map(|(k, _)| *k)
+    }
+
+    /// Lines of help with active bindings
+    pub fn help(&self) -> Vec<String> {
+        const HELP: [(&str, &[&str]); 9] = [
+            ("move", &["move-up", "move-left", "move-down", "move-right"]),
+            ("use block", &["interact"]),
+            ("inventory/items", &["inventory", "items"]),
+            ("items page", &["prev-page", "next-page"]),
+            ("select item", &["cursor-up", "cursor-down"]),
+            ("use/drop item", &["use", "drop"]),
+            ("inspect/sort", &["inspect", "sort"]),
+            ("save/load", &["save", "load"]),
+            ("help/quit", &["help", "quit"]),
+        ];
+        let mut lines = Vec::new();
+        for (description, names) in HELP {
+            let keys: Vec<String> = names
+                .iter()
+                .map(|name| match command(name).and_then(|c| self.key_of(c)) {
+                    Some(key) => key.to_string(),
+                    None => "-".to_owned(),
+                })
+                .collect();
+            lines.push(format!("{} {description}", keys.join("/")));
+        }
+        let first = self.key_of(action(GameAction::Choose(1)));
+        let last = self.key_of(action(GameAction::Choose(9)));
+        if let (Some(first), Some(last)) = (first, last) {
+            lines.push(format!("{first}-{last} choose/select"));
+        }
+        lines
+    }
+}
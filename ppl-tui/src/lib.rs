@@ -0,0 +1,55 @@
+//! Parts of terminal frontend shared by `ppl-tui` and `ppl-server`: key bindings and
+//! drawing over the game.
+
+use ppl_game::{
+    game::{Game, GameHandle, LoreContents},
+    ui::{self, Color, Fragment, TextFragment, TextFragmentFmt},
+};
+
+use keymap::Keymap;
+
+pub mod keymap;
+
+/// Shows active key bindings in lore until next input
+pub fn show_help<UI: ui::Context>(
+    handle: &mut GameHandle<UI>,
+    keymap: &Keymap,
+) -> Result<(), UI::Error> {
+    handle.close_dialogue();
+    handle.lore = LoreContents::Nothing;
+    let mut l = handle.ui.lore();
+    l.clear()?;
+    l.set_color(Color::GrowingWheatBlock)?;
+    writeln!(l, "KEYS")?;
+    l.set_color(Color::Normal)?;
+    for line in keymap.help() {
+        writeln!(l, "{line}")?;
+    }
+    Ok(())
+}
+
+/// Redraws whole screen after terminal resize
+pub fn redraw_screen<UI: ui::Context>(
+    game: &mut Game<UI>,
+    keymap: &Keymap,
+    help_shown: bool,
+) -> Result<(), UI::Error> {
+    game.redraw_screen()?;
+    if help_shown {
+        show_help(&mut game.handle, keymap)?;
+    }
+    game.handle.ui.apply()
+}
+
+/// Writes message to the second status line
+pub fn status_message<UI: ui::Context>(
+    handle: &mut GameHandle<UI>,
+    color: Color,
+    msg: impl std::fmt::Display,
+) -> Result<(), UI::Error> {
+    let mut s = handle.ui.status();
+    s.clear_line(1)?;
+    s.set_color(color)?;
+    write!(s, " {msg}")?;
+    s.set_color(Color::Normal)
+}
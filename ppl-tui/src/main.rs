@@ -1,39 +1,257 @@
 use ppl_game::{
-    game::{Game, GameAction},
-    ui::Context,
-    ui_impls::tui,
+    assets::registry::Registry,
+    clock::{Clock, TickMode},
+    game::Game,
+    replay::{Input, Recorder, Recording, Verdict},
+    ui::{self, Color, Context},
+    ui_impls::{
+        headless,
+        tui::{
+            self,
+            input::{InputEvent, InputReader, Poll},
+        },
+    },
 };
-use std::io::{stdin, Read};
+use std::{
+    io::stdin,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use ppl_tui::{
+    keymap::{Command, Keymap},
+    redraw_screen, show_help, status_message,
+};
+
+/// Path of save file, relative to working directory.
+const SAVE_PATH: &str = "ppl-save.ron";
+
+/// Command line arguments
+#[derive(Default)]
+struct Args {
+    /// `--seed <u64>`, random if not set
+    seed: Option<u64>,
+    /// `--record <path>`, record session into file
+    record: Option<PathBuf>,
+    /// `--replay <path>`, replay session from file
+    replay: Option<PathBuf>,
+    /// `--speed <f64>`, replay speed
+    speed: Option<f64>,
+    /// `--headless`, replay without terminal
+    headless: bool,
+    /// `--keymap <preset or path>`, see [`Keymap::load`]
+    keymap: Option<String>,
+    /// `--tick <ms>`, real-time mode with tick interval
+    tick: Option<Duration>,
+    /// `--things <path>`, more block and item definitions, see [`Registry::load`]
+    things: Option<PathBuf>,
+}
+
+impl Args {
+    fn parse() -> Result<Self, String> {
+        let mut args = Self::default();
+        let mut iter = std::env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            let mut value = || iter.next().ok_or(format!("{arg} requires a value"));
+            match arg.as_str() {
+                "--seed" => {
+                    let value = value()?;
+                    let seed = value
+                        .parse()
+                        .map_err(|e| format!("invalid seed `{value}`: {e}"))?;
+                    args.seed = Some(seed);
+                }
+                "--record" => args.record = Some(value()?.into()),
+                "--replay" => args.replay = Some(value()?.into()),
+                "--speed" => {
+                    let value = value()?;
+                    let speed = value
+                        .parse()
+                        .ok()
+                        .filter(|v: &f64| *v > 0.0)
+                        .ok_or(format!("invalid speed `{value}`"))?;
+                    args.speed = Some(speed);
+                }
+                "--headless" => args.headless = true,
+                "--keymap" => args.keymap = Some(value()?),
+                "--things" => args.things = Some(value()?.into()),
+                "--tick" => {
+                    let value = value()?;
+                    let ms = value
+                        .parse()
+                        .ok()
+                        .filter(|v: &u64| *v > 0)
+                        .ok_or(format!("invalid tick interval `{value}`"))?;
+                    args.tick = Some(Duration::from_millis(ms));
+                }
+                _ => return Err(format!("unknown argument `{arg}`")),
+            }
+        }
+
+        if args.replay.is_some()
+            && (args.seed.is_some() || args.record.is_some() || args.tick.is_some())
+        {
+            return Err("--replay can't be used with --seed, --record or --tick".to_owned());
+        }
+        if args.replay.is_none() && (args.speed.is_some() || args.headless) {
+            return Err("--speed and --headless require --replay".to_owned());
+        }
+        Ok(args)
+    }
+}
+
+/// Passes input to the game and records it
+fn play<UI: ui::Context>(
+    game: &mut Game<UI>,
+    recorder: &mut Option<Recorder>,
+    input: Input,
+) -> Result<(), UI::Error> {
+    if let Some(recorder) = recorder {
+        recorder.record(input);
+    }
+    game.do_input(input)
+}
+
+/// Replays recording without terminal, prints final screen and verdict
+fn replay_headless(recording: &Recording) -> Result<(), Box<dyn std::error::Error>> {
+    let mut game = recording.start(headless::Context::new());
+    game.redraw_all()?;
+    game.draw_player_info()?;
+    let verdict = recording.replay(&mut game)?;
+    print!("{}", game.handle.ui.render_to_string());
+    println!("replay: {verdict:?}");
+    if let Verdict::Diverged { .. } | Verdict::Error(_) = verdict {
+        std::process::exit(1);
+    }
+    Ok(())
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let mut game = tui::Context::init()?.map(Game::new);
+    let args = Args::parse()?;
+    if let Some(path) = &args.things {
+        Registry::load(path)?
+            .install()
+            .map_err(|_| "definitions are already in use")?;
+    }
+    let keymap = Keymap::load(args.keymap.as_deref())?;
+    let replay = args.replay.as_ref().map(Recording::load_from).transpose()?;
+    if let (Some(recording), true) = (&replay, args.headless) {
+        return replay_headless(recording);
+    }
+
+    let seed = replay.as_ref().map(|r| r.seed).or(args.seed);
+    let mut game = tui::Context::init()?.map(|ui| match seed {
+        Some(seed) => Game::new_with_seed(ui, seed),
+        None => Game::new(ui),
+    });
+    game.tick_mode = match (&replay, args.tick) {
+        (Some(recording), _) => recording.tick_mode,
+        (None, Some(interval)) => TickMode::RealTime(interval),
+        (None, None) => TickMode::TurnBased,
+    };
+    let mut recorder = args
+        .record
+        .as_ref()
+        .map(|_| Recorder::new(game.seed, game.tick_mode));
 
-    game.handle.ui.draw_borders()?;
     game.redraw_all()?;
     game.draw_player_info()?;
     game.handle.ui.apply()?;
-    let buff = &mut [0];
 
-    while stdin().read_exact(buff).is_ok() {
-        match buff[0] {
-            b'w' => game.do_action(GameAction::MoveUp)?,
-            b'a' => game.do_action(GameAction::MoveLeft)?,
-            b's' => game.do_action(GameAction::MoveDown)?,
-            b'd' => game.do_action(GameAction::MoveRight)?,
+    if let Some(recording) = &replay {
+        let speed = args.speed.unwrap_or(1.0);
+        let mut last_ms = 0;
+        for event in &recording.events {
+            let delay = event.at_ms.saturating_sub(last_ms);
+            std::thread::sleep(Duration::from_millis(delay).div_f64(speed));
+            last_ms = event.at_ms;
 
-            b'e' => game.do_action(GameAction::Interact)?,
-            b'i' => game.handle.toggle_inventory(),
-            b'o' => game.handle.toggle_items(),
+            game.do_input(event.input)?;
+            game.handle.ui.apply()?;
+        }
+        match recording.verify(&game) {
+            Verdict::Matched => {
+                status_message(&mut game.handle, Color::MaxValue, "replay matched")?
+            }
+            verdict => status_message(
+                &mut game.handle,
+                Color::Health,
+                format!("replay: {verdict:?}"),
+            )?,
+        }
+        game.handle.ui.apply()?;
+    }
 
-            b'q' => break,
+    let mut input = InputReader::new(stdin());
+    let mut clock = match game.tick_mode {
+        TickMode::RealTime(interval) => Some(Clock::new(interval, Instant::now())),
+        TickMode::TurnBased => None,
+    };
+    let mut help_shown = false;
+    loop {
+        if game.handle.ui.check_resize() {
+            redraw_screen(&mut game, &keymap, help_shown)?;
+        }
+        if let Some(clock) = &mut clock {
+            let ticks = clock.due(Instant::now());
+            for _ in 0..ticks {
+                play(&mut game, &mut recorder, Input::Tick)?;
+            }
+            if ticks != 0 {
+                if help_shown {
+                    show_help(&mut game.handle, &keymap)?;
+                }
+                game.handle.ui.apply()?;
+            }
+        }
 
-            _ => continue,
+        let timeout = clock.as_ref().map(|c| c.until_next(Instant::now()));
+        let key = match input.poll_event(timeout)? {
+            Poll::Event(InputEvent::Key(key)) => key,
+            Poll::Event(InputEvent::Resize(size)) => {
+                game.handle.ui.resize(size);
+                redraw_screen(&mut game, &keymap, help_shown)?;
+                continue;
+            }
+            Poll::Event(InputEvent::Mouse(_)) | Poll::Timeout | Poll::Interrupted => continue,
+            Poll::Closed => break,
+        };
+        let command = keymap.get(key);
+        help_shown = command == Some(Command::Help);
+        match command {
+            Some(Command::Input(input)) => play(&mut game, &mut recorder, input)?,
+            Some(Command::Save) => match game.save_to(SAVE_PATH) {
+                Ok(()) => status_message(
+                    &mut game.handle,
+                    Color::Normal,
+                    format!("game saved to {SAVE_PATH}"),
+                )?,
+                Err(e) => status_message(&mut game.handle, Color::Health, e)?,
+            },
+            Some(Command::Load) if recorder.is_some() => status_message(
+                &mut game.handle,
+                Color::Health,
+                "can't load while recording",
+            )?,
+            Some(Command::Load) => match game.load_from(SAVE_PATH) {
+                Ok(()) => game.redraw_screen()?,
+                Err(e) => status_message(&mut game.handle, Color::Health, e)?,
+            },
+            Some(Command::Help) => show_help(&mut game.handle, &keymap)?,
+            Some(Command::Quit) => break,
+            None => continue,
         }
 
-        game.draw_lore()?;
-        game.draw_player_info()?;
         game.handle.ui.apply()?;
     }
 
+    let seed = game.seed;
+    let recording = recorder.map(|r| r.finish(&game)).transpose()?;
+    drop(game);
+    eprintln!("game seed: {seed}");
+    if let (Some(recording), Some(path)) = (recording, args.record) {
+        recording.save_to(&path)?;
+        eprintln!("session recorded to {}", path.display());
+    }
     Ok(())
 }
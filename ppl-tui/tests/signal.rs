@@ -0,0 +1,147 @@
+//! Runs `ppl-tui` on a pseudo-terminal and kills it
+
+use std::{
+    ffi::CStr,
+    fs::{File, OpenOptions},
+    io::{self, Read},
+    os::{
+        fd::{AsRawFd, FromRawFd},
+        unix::{fs::OpenOptionsExt, process::ExitStatusExt},
+    },
+    process::{Command, Stdio},
+    time::{Duration, Instant},
+};
+
+/// How long terminal waits for output that must come
+const WAIT: Duration = Duration::from_secs(10);
+
+/// Leaves alternate screen
+const LEAVE: &[u8] = b"\x1b[?1049l";
+
+/// Pseudo-terminal: master side is read by test, slave side is terminal of game
+struct Pty {
+    master: File,
+    slave: File,
+    /// Everything game has written
+    out: Vec<u8>,
+}
+
+impl Pty {
+    /// Opens pty of 80x24
+    fn open() -> io::Result<Self> {
+        // SAFETY: fd is checked and owned by `File`, name buffer is null-terminated
+        let (master, name) = unsafe {
+            let fd = libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY);
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let master = File::from_raw_fd(fd);
+            let mut name = [0; 128];
+            if libc::grantpt(fd) != 0
+                || libc::unlockpt(fd) != 0
+                || libc::ptsname_r(fd, name.as_mut_ptr(), name.len()) != 0
+            {
+                return Err(io::Error::last_os_error());
+            }
+            let name = CStr::from_ptr(name.as_ptr()).to_str().unwrap().to_owned();
+            (master, name)
+        };
+        let slave = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NOCTTY)
+            .open(name)?;
+        let size = libc::winsize {
+            ws_row: 24,
+            ws_col: 80,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+        // SAFETY: `size` is valid `winsize`
+        if unsafe { libc::ioctl(slave.as_raw_fd(), libc::TIOCSWINSZ, &size) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self {
+            master,
+            slave,
+            out: Vec::new(),
+        })
+    }
+
+    /// Reads output until it contains `needle`
+    fn read_until(&mut self, needle: &[u8]) {
+        let start = Instant::now();
+        while !self.out.windows(needle.len()).any(|w| w == needle) {
+            let left = WAIT.checked_sub(start.elapsed()).unwrap_or_else(|| {
+                panic!(
+                    "no {needle:?} in output: {:?}",
+                    String::from_utf8_lossy(&self.out)
+                )
+            });
+            let mut poll = libc::pollfd {
+                fd: self.master.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            // SAFETY: one valid `pollfd`
+            if unsafe { libc::poll(&mut poll, 1, left.as_millis() as libc::c_int) } <= 0 {
+                continue;
+            }
+            let buf = &mut [0; 4096];
+            match self.master.read(buf) {
+                Ok(n) => self.out.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => panic!("{e}"),
+            }
+        }
+    }
+
+    /// Local flags of terminal
+    fn lflag(&self) -> libc::tcflag_t {
+        // SAFETY: `termios` is plain data, `tcgetattr` only writes it
+        unsafe {
+            let mut termios: libc::termios = std::mem::zeroed();
+            assert_eq!(libc::tcgetattr(self.slave.as_raw_fd(), &mut termios), 0);
+            termios.c_lflag
+        }
+    }
+}
+
+#[test]
+fn terminal_is_restored_on_sigterm() {
+    let mut pty = Pty::open().unwrap();
+    let dir = std::env::temp_dir().join(format!("ppl-tui-signal-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let stdio = || Stdio::from(pty.slave.try_clone().unwrap());
+    let mut game = Command::new(env!("CARGO_BIN_EXE_ppl-tui"))
+        .args(["--keymap", "wasd", "--seed", "1"])
+        .current_dir(&dir)
+        .env("TERM", "xterm")
+        .stdin(stdio())
+        .stdout(stdio())
+        .stderr(stdio())
+        .spawn()
+        .unwrap();
+
+    pty.read_until(b"-[ MAP ]-");
+    let lflag = pty.lflag();
+    assert_eq!(
+        lflag & (libc::ECHO | libc::ICANON),
+        0,
+        "terminal is not set up"
+    );
+
+    // SAFETY: plain syscall
+    assert_eq!(
+        unsafe { libc::kill(game.id() as libc::pid_t, libc::SIGTERM) },
+        0
+    );
+    let status = game.wait().unwrap();
+    assert_eq!(status.signal(), Some(libc::SIGTERM));
+    pty.read_until(LEAVE);
+    let lflag = pty.lflag();
+    assert_eq!(lflag & libc::ECHO, libc::ECHO);
+    assert_eq!(lflag & libc::ICANON, libc::ICANON);
+
+    _ = std::fs::remove_dir_all(&dir);
+}